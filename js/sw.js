@@ -7,7 +7,7 @@ const FILES_TO_CACHE = [
     "/res/icon.png",
 ];
 // Cache name so we can install updates
-const VERSION = 6;
+const VERSION = 7;
 const CACHE_NAME = `pairandomizer-v${VERSION}`;
 
 // On install,
@@ -30,6 +30,8 @@ self.addEventListener("message", e => {
 // Utility function to fetch something from the server, also storing it in cache.
 const fetch_then_cache = async (request) => {
     const response = await fetch(request);
+    // Don't cache errors or "304 Not Modified" answers to conditional requests
+    if (!response.ok) { return response; }
     const cache = await caches.open(CACHE_NAME);
     console.log(`[Service Worker] Caching new resource: ${request.url}`);
     cache.put(request, response.clone());
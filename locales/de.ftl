@@ -0,0 +1,188 @@
+## Navigation bar
+
+app-title = Pairandomizer
+refresh-scenarios = Szenarien neu laden
+open-history = Verlauf
+open-share-roster = Namen teilen
+open-settings = Einstellungen
+
+## Main page
+
+names-placeholder = Namen eingeben, einen pro Zeile
+randomize = Auslosen! ({ $count ->
+        [one] ein Name
+       *[other] { $count } Namen
+    })
+status-loading = ⏳ Lädt…
+status-refreshing = ⏳ Aktualisiert… ({ $cached })
+status-loaded = ✅ Geladen vom Server: { $server }
+status-loaded-waiting = ✅ Geladen, warte auf Daten…
+status-error = ❌ Fehler: { $error }
+status-cached = zwischengespeicherte Daten von { $server }
+status-retrying = neuer Versuch in { $seconds ->
+        [one] einer Sekunde
+       *[other] { $seconds } Sekunden
+    }
+last-updated = Zuletzt aktualisiert: { $time }
+retry = Erneut versuchen
+close = Schließen
+issues-title = Probleme in den Szenarien
+issues-tooltip = { $count ->
+        [one] In den Szenarien wurde ein Problem gefunden
+       *[other] In den Szenarien wurden { $count } Probleme gefunden
+    }
+waiting-for-scenarios = Warte, bis die Szenarien geladen sind
+not-enough-names = { $count ->
+        [one] Mindestens einen Namen eingeben
+       *[other] Mindestens { $count } Namen eingeben, einen pro Zeile
+    }
+cant-randomize = Auslosen nicht möglich
+scenario-beta = 🧪 { $name } (Beta)
+scenario-local = 📁 { $name }
+
+## Result
+
+new-message = Andere Nachricht wählen
+re-pair = Jemanden mit einer anderen Gruppe tauschen
+reroll-all = Alle neu würfeln
+lock-group = Diese Gruppe beim Neuwürfeln behalten
+unlock-group = Diese Gruppe wieder neu würfeln
+reroll-unlocked = Den Rest neu würfeln
+scene-unavailable = Die Szene ist nicht mehr verfügbar, deshalb kann dieses Ergebnis nicht neu gewürfelt werden.
+copy-plain = Als Text kopieren
+copy-markdown = Als Markdown kopieren
+copy-chat = Für Slack/Discord kopieren
+copied = In die Zwischenablage kopiert.
+copy-failed = Kopieren fehlgeschlagen: { $error }
+share = Teilen
+share-failed = Teilen fehlgeschlagen: { $error }
+copy-link = Link kopieren
+link-copied = Link in die Zwischenablage kopiert.
+link-invalid = Der Link kann nicht geöffnet werden
+link-scenarios-unavailable = Die Szenarien konnten nicht geladen werden: { $error }
+link-malformed = Der Link ist unvollständig oder beschädigt ({ $error }).
+result-link = Link zum Ergebnis
+show-qr-code = QR-Code
+qr-code-failed = Kein QR-Code möglich: { $error }
+
+## History
+
+history = Verlauf
+search-by-name = Nach Namen suchen
+history-empty = Noch keine Ergebnisse.
+history-no-matches = Keine Ergebnisse mit diesem Namen.
+open = Öffnen
+
+## Statistics
+
+statistics = Statistik
+statistics-results = { $count ->
+        [one] Basierend auf einem Ergebnis.
+       *[other] Basierend auf { $count } Ergebnissen.
+    }
+pair-frequency = Wie oft wer mit wem in einer Gruppe war
+never-paired = Noch nie in einer Gruppe
+everyone-paired = Alle waren schon mit allen anderen in einer Gruppe.
+most-used-scenarios = Meistgenutzte Szenarien
+most-used-scenes = Meistgenutzte Szenen
+solo-counts = Wie oft allein
+
+## Roster links
+
+share-roster = Namen teilen
+roster-names = Ein Link zu { $count } Namen.
+include-selection = Gewähltes Szenario und gewählte Szene mitschicken
+import-roster = Namen importieren
+import-roster-question = Deine Namen durch diese ersetzen?
+import = Importieren
+roster-selection = Wählt außerdem { $selection } aus.
+roster-selection-unavailable = Das gewählte Szenario kann nicht importiert werden: { $error }
+
+## Randomization errors
+
+error-no-scenarios = Es gibt keine Szenarien zur Auswahl.
+error-no-scenes = Das Szenario „{ $scenario }“ hat keine Szenen.
+error-no-messages = Die Szene „{ $scene }“ hat keine Nachrichten.
+error-not-enough-names = { $count ->
+        [one] Es wird mindestens ein Name benötigt.
+       *[other] Es werden mindestens { $count } Namen benötigt.
+    }
+
+## Loading errors
+
+error-format-too-new = Die Datei ist im Format { $found }, unterstützt wird aber nur { $supported }. Bitte aktualisiere die App.
+error-format-too-old = Die Datei ist im Format { $found }, das nicht mehr unterstützt wird (erwartet: { $supported }).
+
+error-unreachable = { $url } ist nicht erreichbar: { $error }
+error-status = { $url } antwortete mit { $status } { $text }
+error-scenario-unavailable = Das Szenario { $filename } ist nicht verfügbar.
+error-scene-unavailable = Die Szene „{ $scene }“ von { $filename } ist nicht verfügbar.
+
+## Issues in files
+
+severity-warning = Warnung
+severity-error = Fehler
+issue-at = { $location }: { $issue }
+issue-scene = Szene „{ $name }“
+issue-scene-number = Szene { $number }
+issue-translation = Übersetzung „{ $lang }“
+issue-message = Nachricht { $number }
+issue-triple-message = Dreier-Nachricht { $number }
+issue-solo-message = Solo-Nachricht { $number }
+issue-no-scenarios = keine Szenarien
+issue-duplicate-filename = { $filename } ist mehrfach aufgeführt
+issue-no-scenes = keine Szenen
+issue-unnamed-scene = kein Name
+issue-duplicate-scene-name = doppelter Szenenname
+issue-no-messages = keine Nachrichten
+issue-duplicate-message = doppelte Nachricht
+issue-missing-placeholder = { $placeholder } fehlt
+issue-unknown-placeholder = unbekannter Platzhalter { $placeholder }
+
+## Settings
+
+settings = Einstellungen
+setting-language = Sprache:
+language-automatic = Automatisch ({ $languages })
+setting-ignore-language = Alle Sprachen erlauben
+setting-enable-beta = Beta-Szenarien aktivieren
+setting-fixed-scenario = Festes Szenario:
+setting-fixed-scene = Feste Szene:
+none = Keins
+
+## Local scenarios
+
+local-scenarios = Lokale Szenarien
+new-scenario = Neues Szenario
+edit-scenario = Szenario bearbeiten
+import-scenario = Szenario importieren
+imported = „{ $name }“ importiert
+imported-with-warnings = „{ $name }“ importiert, aber: { $warnings }
+import-failed = Import fehlgeschlagen: { $error }
+edit = Bearbeiten
+remove = Entfernen
+
+## Scenario editor
+
+name = Name
+language = Sprache
+language-placeholder = z. B. de
+scenes = Szenen
+scene-name = Name der Szene
+messages-placeholder = Eine Nachricht pro Zeile, z. B. %1$s und %2$s gehen schwimmen.
+triple-messages-placeholder = Nachrichten für Dreiergruppen, z. B. %1$s, %2$s und %3$s bauen eine Sandburg.
+solo-messages-placeholder = Nachrichten für einen übrigen Namen, z. B. %1$s liest ein Buch.
+add-scene = Szene hinzufügen
+scenario-solo-messages = Einzelnachrichten
+scenario-solo-messages-placeholder = Nachrichten für einen übrigen Namen, für Szenen ohne eigene.
+preview = Vorschau
+scenario-needs-name = Das Szenario braucht einen Namen.
+cancel = Abbrechen
+save = Speichern
+
+## Updates
+
+update-available = Update verfügbar!
+update-now = Jetzt aktualisieren?
+ignore = Ignorieren
+yes = Ja
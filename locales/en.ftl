@@ -0,0 +1,191 @@
+## Navigation bar
+
+app-title = Pairandomizer
+refresh-scenarios = Refresh scenarios
+open-history = History
+open-share-roster = Share names
+open-settings = Settings
+
+## Main page
+
+names-placeholder = Enter the names, one per line
+randomize = Randomize! ({ $count ->
+        [one] one name
+       *[other] { $count } names
+    })
+status-loading = ⏳ Loading…
+status-refreshing = ⏳ Refreshing… ({ $cached })
+status-loaded = ✅ Loaded from server: { $server }
+status-loaded-waiting = ✅ Loaded, waiting for data…
+status-error = ❌ Error: { $error }
+status-cached = using cached data from { $server }
+status-retrying = retrying in { $seconds ->
+        [one] one second
+       *[other] { $seconds } seconds
+    }
+last-updated = Last updated: { $time }
+retry = Retry
+close = Close
+issues-title = Problems in the scenarios
+issues-tooltip = { $count ->
+        [one] A problem was found in the scenarios
+       *[other] { $count } problems were found in the scenarios
+    }
+waiting-for-scenarios = Waiting for scenarios to load
+not-enough-names = { $count ->
+        [one] Enter at least one name
+       *[other] Enter at least { $count } names, one per line
+    }
+cant-randomize = Can't randomize
+scenario-beta = 🧪 { $name } (beta)
+scenario-local = 📁 { $name }
+
+## Result
+
+new-message = Pick another message
+re-pair = Swap someone with another group
+reroll-all = Re-roll all
+lock-group = Keep this group when re-rolling
+unlock-group = Re-roll this group again
+reroll-unlocked = Re-roll the rest
+scene-unavailable = The scene is no longer available, so this result can't be re-rolled.
+copy-plain = Copy as text
+copy-markdown = Copy as Markdown
+copy-chat = Copy for Slack/Discord
+copied = Copied to the clipboard.
+copy-failed = Copying failed: { $error }
+share = Share
+share-failed = Sharing failed: { $error }
+copy-link = Copy link
+link-copied = Link copied to the clipboard.
+link-invalid = Can't open the link
+link-scenarios-unavailable = The scenarios couldn't be loaded: { $error }
+link-malformed = The link is incomplete or damaged ({ $error }).
+result-link = Link to the result
+show-qr-code = QR code
+qr-code-failed = No QR code possible: { $error }
+
+## History
+
+history = History
+search-by-name = Search by name
+history-empty = No results yet.
+history-no-matches = No results with this name.
+open = Open
+
+## Statistics
+
+statistics = Statistics
+statistics-results = { $count ->
+        [one] Based on one result.
+       *[other] Based on { $count } results.
+    }
+pair-frequency = How often who was grouped with whom
+never-paired = Never grouped together
+everyone-paired = Everyone has been grouped with everyone else.
+most-used-scenarios = Most used scenarios
+most-used-scenes = Most used scenes
+solo-counts = Times alone
+
+## Roster links
+
+share-roster = Share names
+roster-names = { $count ->
+    [one] A link to { $count } name.
+   *[other] A link to { $count } names.
+}
+include-selection = Include the selected scenario and scene
+import-roster = Import names
+import-roster-question = Replace your names with these?
+import = Import
+roster-selection = Also selects { $selection }.
+roster-selection-unavailable = The selected scenario can't be imported: { $error }
+
+## Randomization errors
+
+error-no-scenarios = There are no scenarios to pick from.
+error-no-scenes = The scenario “{ $scenario }” has no scenes.
+error-no-messages = The scene “{ $scene }” has no messages.
+error-not-enough-names = { $count ->
+        [one] At least one name is needed.
+       *[other] At least { $count } names are needed.
+    }
+
+## Loading errors
+
+error-format-too-new = The file is in format { $found }, but only { $supported } is supported. Please update the app.
+error-format-too-old = The file is in format { $found }, which is no longer supported (expected { $supported }).
+
+error-unreachable = Can't reach { $url }: { $error }
+error-status = { $url } answered with { $status } { $text }
+error-scenario-unavailable = The scenario { $filename } isn't available.
+error-scene-unavailable = The scene “{ $scene }” of { $filename } isn't available.
+
+## Issues in files
+
+severity-warning = Warning
+severity-error = Error
+issue-at = { $location }: { $issue }
+issue-scene = scene “{ $name }”
+issue-scene-number = scene { $number }
+issue-translation = translation “{ $lang }”
+issue-message = message { $number }
+issue-triple-message = triple message { $number }
+issue-solo-message = solo message { $number }
+issue-no-scenarios = no scenarios
+issue-duplicate-filename = { $filename } is listed more than once
+issue-no-scenes = no scenes
+issue-unnamed-scene = no name
+issue-duplicate-scene-name = duplicate scene name
+issue-no-messages = no messages
+issue-duplicate-message = duplicate message
+issue-missing-placeholder = missing { $placeholder }
+issue-unknown-placeholder = unknown placeholder { $placeholder }
+
+## Settings
+
+settings = Settings
+setting-language = Language:
+language-automatic = Automatic ({ $languages })
+setting-ignore-language = Allow all languages
+setting-enable-beta = Enable beta scenarios
+setting-fixed-scenario = Fixed scenario:
+setting-fixed-scene = Fixed scene:
+none = None
+
+## Local scenarios
+
+local-scenarios = Local scenarios
+new-scenario = New scenario
+edit-scenario = Edit scenario
+import-scenario = Import scenario
+imported = Imported “{ $name }”
+imported-with-warnings = Imported “{ $name }”, but: { $warnings }
+import-failed = Import failed: { $error }
+edit = Edit
+remove = Remove
+
+## Scenario editor
+
+name = Name
+language = Language
+language-placeholder = e.g. en
+scenes = Scenes
+scene-name = Scene name
+messages-placeholder = One message per line, e.g. %1$s and %2$s go swimming.
+triple-messages-placeholder = Messages for groups of three, e.g. %1$s, %2$s and %3$s build a sandcastle.
+solo-messages-placeholder = Messages for a single name left over, e.g. %1$s reads a book.
+add-scene = Add scene
+scenario-solo-messages = Solo messages
+scenario-solo-messages-placeholder = Messages for a single name left over, used by scenes without their own.
+preview = Preview
+scenario-needs-name = The scenario needs a name.
+cancel = Cancel
+save = Save
+
+## Updates
+
+update-available = Update available!
+update-now = Update now?
+ignore = Ignore
+yes = Yes
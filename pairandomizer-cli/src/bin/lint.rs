@@ -0,0 +1,39 @@
+//! Checks an index and all scenario files it lists for mistakes.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use pairandomizer_core::Severity;
+
+/// Checks an index and all scenario files it lists for mistakes.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// The index file, or a directory containing an index.json.
+    #[arg(default_value = ".")]
+    path: PathBuf,
+    /// Fail on warnings, too.
+    #[arg(short = 'D', long)]
+    deny_warnings: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let (_, diagnostics) = pairandomizer_cli::load(&args.path);
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    eprintln!("{errors} error(s), {warnings} warning(s)");
+    if errors > 0 || (args.deny_warnings && warnings > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
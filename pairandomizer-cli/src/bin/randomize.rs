@@ -0,0 +1,199 @@
+//! Randomizes a list of names with the scenarios from a local directory.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, ValueEnum};
+use pairandomizer_core::{
+    fallback_chain, parse_names, rng_from_seed, LoadedIndex, PickOptions, RandomizedMsg, Severity,
+    TextFormat, DEFAULT_GROUP_SIZE,
+};
+use serde::Serialize;
+use unic_langid::LanguageIdentifier;
+
+/// Randomizes a list of names with the scenarios from a local directory.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// File with one name per line; reads stdin if missing or "-".
+    names: Option<PathBuf>,
+    /// The index file, or a directory containing an index.json.
+    #[arg(short, long, default_value = ".")]
+    index: PathBuf,
+    /// Use this scenario, by name or filename.
+    #[arg(long)]
+    scenario: Option<String>,
+    /// Use this scene of the scenario, by name in any of its languages.
+    #[arg(long, requires = "scenario")]
+    scene: Option<String>,
+    /// Seed for the randomization, to reproduce an earlier result.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// How many names go into each group.
+    #[arg(short, long, default_value_t = DEFAULT_GROUP_SIZE, value_parser = parse_group_size)]
+    group_size: usize,
+    /// Prefer scenarios in this language; may be given several times, most
+    /// preferred first. Any language is used if missing.
+    #[arg(short, long)]
+    lang: Vec<LanguageIdentifier>,
+    /// Include beta scenarios.
+    #[arg(long)]
+    beta: bool,
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// The title, followed by one message per line.
+    Text,
+    /// A bold title and a bulleted list with bold names.
+    Markdown,
+    /// Like markdown, but for pasting into Slack or Discord.
+    Chat,
+    /// A JSON object with the seed, scenario, scene and messages.
+    Json,
+}
+
+/// The result as printed with `--format json`.
+#[derive(Serialize)]
+struct Output<'a> {
+    seed: u64,
+    scenario: &'a str,
+    filename: &'a str,
+    scene: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lang: Option<&'a str>,
+    messages: Vec<OutputMsg<'a>>,
+}
+#[derive(Serialize)]
+struct OutputMsg<'a> {
+    names: &'a [String],
+    text: String,
+}
+
+fn parse_group_size(arg: &str) -> Result<usize> {
+    let size = arg.parse()?;
+    if size < 2 {
+        bail!("groups need at least two names");
+    }
+    Ok(size)
+}
+
+fn read_names(path: Option<&PathBuf>) -> Result<Vec<String>> {
+    let text = match path {
+        Some(path) if path.as_os_str() != "-" => {
+            fs::read_to_string(path).with_context(|| format!("{}", path.display()))?
+        }
+        _ => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    Ok(parse_names(&text))
+}
+
+/// Turns the `--scenario` and `--scene` flags into positions in the index.
+fn pick_options(args: &Args, index: &LoadedIndex) -> Result<PickOptions> {
+    let mut options = PickOptions {
+        ignore_language: args.lang.is_empty(),
+        enable_beta: args.beta,
+        ..PickOptions::default()
+    };
+    let Some(wanted) = &args.scenario else {
+        return Ok(options);
+    };
+    let Some(scenario_index) = index
+        .scenarios
+        .iter()
+        .position(|(meta, _)| meta.name == *wanted || meta.filename == *wanted)
+    else {
+        let available = index
+            .scenarios
+            .iter()
+            .map(|(meta, _)| format!("\n  {} ({})", meta.name, meta.filename))
+            .collect::<String>();
+        bail!("unknown scenario {wanted:?}, available are:{available}");
+    };
+    options.scenario_index = Some(scenario_index);
+    if let Some(wanted) = &args.scene {
+        let scenes = &index.scenarios[scenario_index].1.scenes;
+        // Scenes may be given by their name in any language
+        let Some(scene_index) = scenes.iter().position(|scene| {
+            scene.name == *wanted
+                || scene
+                    .translations
+                    .values()
+                    .any(|translation| translation.name.as_ref() == Some(wanted))
+        }) else {
+            let available = scenes
+                .iter()
+                .map(|scene| format!("\n  {}", scene.name))
+                .collect::<String>();
+            bail!("unknown scene {wanted:?}, available are:{available}");
+        };
+        options.scene_index = Some(scene_index);
+    }
+    Ok(options)
+}
+
+fn run(args: &Args) -> Result<()> {
+    let names = read_names(args.names.as_ref())?;
+    let (index, diagnostics) = pairandomizer_cli::load(&args.index);
+    for diagnostic in diagnostics.iter().filter(|d| d.severity == Severity::Error) {
+        eprintln!("{diagnostic}");
+    }
+    let Some(index) = index else {
+        bail!("can't read the index at {}", args.index.display());
+    };
+    let options = pick_options(args, &index)?;
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let result = index.randomize(
+        &mut rng_from_seed(seed),
+        &options,
+        &fallback_chain(&args.lang),
+        &names,
+        args.group_size,
+    )?;
+    match args.format {
+        Format::Text => println!("{}", result.format(TextFormat::Plain)),
+        Format::Markdown => println!("{}", result.format(TextFormat::Markdown)),
+        Format::Chat => println!("{}", result.format(TextFormat::Chat)),
+        Format::Json => {
+            let output = Output {
+                seed,
+                scenario: &result.scenario.name,
+                filename: &result.scenario.filename,
+                scene: &result.scene,
+                lang: result.lang.as_deref(),
+                messages: result
+                    .messages
+                    .iter()
+                    .map(|message: &RandomizedMsg| OutputMsg {
+                        names: &message.names,
+                        text: message.to_string(),
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Reading index and scenario files from disk, shared by the command line
+//! tools.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use pairandomizer_core::{
+    parse_versioned, Index, Issue, IssueKind, LoadedIndex, Scenario, Scene, Severity, TemplateKind,
+    INDEX_FILENAME,
+};
+use serde::de::DeserializeOwned;
+
+/// A problem found in a file, pointing at the line it is on where possible.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {}: {}", self.severity, self.message)
+    }
+}
+
+/// Reads and parses a JSON file, keeping its text around to find lines in.
+fn read<T: DeserializeOwned>(path: &Path) -> Result<(String, T), Diagnostic> {
+    let error = |line, message| Diagnostic {
+        path: path.to_path_buf(),
+        line,
+        severity: Severity::Error,
+        message,
+    };
+    let text = fs::read_to_string(path).map_err(|e| error(None, e.to_string()))?;
+    let data = parse_versioned(&text).map_err(|e| {
+        let line = e.downcast_ref::<serde_json::Error>().map(|e| e.line());
+        error(line, e.to_string())
+    })?;
+    Ok((text, data))
+}
+
+/// Finds the line of the `nth` occurrence of `value` as a JSON string.
+///
+/// This may come up empty if the file escapes characters differently than
+/// serde_json does.
+fn line_of(text: &str, value: &str, nth: usize) -> Option<usize> {
+    let needle = serde_json::to_string(value).ok()?;
+    let (offset, _) = text.match_indices(&needle).nth(nth)?;
+    Some(text[..offset].matches('\n').count() + 1)
+}
+
+/// All messages of a scene, in the order they usually appear in the file.
+fn scene_messages(scene: &Scene) -> impl Iterator<Item = &String> {
+    TemplateKind::ALL
+        .into_iter()
+        .flat_map(move |kind| kind.templates(scene))
+}
+
+/// Finds the line an issue in a scenario file is about.
+fn issue_line(text: &str, scenario: &Scenario, issue: &Issue) -> Option<usize> {
+    if let Some(lang) = &issue.lang {
+        // Translations aren't in a fixed place, so this points at the first
+        // copy of the message
+        let (kind, message_ix) = issue.message?;
+        let message = match &issue.scene {
+            Some((scene_ix, _)) => {
+                let translation = scenario.scenes.get(*scene_ix)?.translations.get(lang)?;
+                kind.translated(translation).get(message_ix)?
+            }
+            None => scenario
+                .translations
+                .get(lang)?
+                .solo_messages
+                .get(message_ix)?,
+        };
+        return line_of(text, message, 0);
+    }
+    match (&issue.scene, issue.message) {
+        (Some((scene_ix, _)), Some((kind, message_ix))) => {
+            let scene = scenario.scenes.get(*scene_ix)?;
+            let message = kind.templates(scene).get(message_ix)?;
+            // Identical messages before this one, in file order
+            let earlier = scenario.scenes[..*scene_ix]
+                .iter()
+                .flat_map(scene_messages)
+                .chain(
+                    TemplateKind::ALL
+                        .into_iter()
+                        .take_while(|k| *k != kind)
+                        .flat_map(|k| k.templates(scene)),
+                )
+                .chain(&kind.templates(scene)[..message_ix])
+                .filter(|m| *m == message)
+                .count();
+            line_of(text, message, earlier)
+        }
+        (Some((scene_ix, _)), None) => {
+            let scene = scenario.scenes.get(*scene_ix)?;
+            let earlier = scenario.scenes[..*scene_ix]
+                .iter()
+                .filter(|s| s.name == scene.name)
+                .count();
+            line_of(text, &scene.name, earlier)
+        }
+        (None, Some((_, message_ix))) => {
+            // Messages outside of scenes are the scenario's solo messages,
+            // which come after the scenes
+            let message = scenario.solo_messages.get(message_ix)?;
+            let earlier = scenario
+                .scenes
+                .iter()
+                .flat_map(scene_messages)
+                .chain(&scenario.solo_messages[..message_ix])
+                .filter(|m| *m == message)
+                .count();
+            line_of(text, message, earlier)
+        }
+        (None, None) => None,
+    }
+}
+
+/// Reads an index file, or the `index.json` in a directory, along with all
+/// the scenario files it lists.
+///
+/// Returns the usable part of what was read (if the index itself could be
+/// read) and every problem found on the way.
+pub fn load(path: &Path) -> (Option<LoadedIndex>, Vec<Diagnostic>) {
+    let index_path = if path.is_dir() {
+        path.join(INDEX_FILENAME)
+    } else {
+        path.to_path_buf()
+    };
+    let dir = index_path.parent().unwrap_or_else(|| Path::new("."));
+    let (index_text, index) = match read::<Index>(&index_path) {
+        Ok(read) => read,
+        Err(diagnostic) => return (None, vec![diagnostic]),
+    };
+
+    let mut diagnostics = Vec::new();
+    for issue in index.validate() {
+        let line = match &issue.kind {
+            IssueKind::DuplicateFilename(filename) => line_of(&index_text, filename, 1),
+            _ => None,
+        };
+        diagnostics.push(Diagnostic {
+            path: index_path.clone(),
+            line,
+            severity: issue.severity,
+            message: issue.to_string(),
+        });
+    }
+
+    let metas = index.all_scenarios().collect::<Vec<_>>();
+    let mut loaded = LoadedIndex::new(index);
+    for meta in metas {
+        let path = dir.join(&meta.filename);
+        let (text, scenario) = match read::<Scenario>(&path) {
+            Ok(read) => read,
+            Err(diagnostic) if !path.exists() => {
+                // Point at the entry in the index instead
+                diagnostics.push(Diagnostic {
+                    path: index_path.clone(),
+                    line: line_of(&index_text, &meta.filename, 0),
+                    message: format!("{}: {}", meta.filename, diagnostic.message),
+                    ..diagnostic
+                });
+                continue;
+            }
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                continue;
+            }
+        };
+        for issue in scenario.validate() {
+            diagnostics.push(Diagnostic {
+                path: path.clone(),
+                line: issue_line(&text, &scenario, &issue),
+                severity: issue.severity,
+                message: issue.to_string(),
+            });
+        }
+        loaded.add_scenario(meta, scenario);
+    }
+    (Some(loaded), diagnostics)
+}
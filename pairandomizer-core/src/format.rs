@@ -0,0 +1,149 @@
+//! Writing out results as text to paste elsewhere.
+
+use core::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{Randomization, RandomizedMsg};
+
+/// How a result is written out by [`Randomization::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextFormat {
+    /// A title line followed by one line per group.
+    Plain,
+    /// A bold title followed by a bulleted list with bold names.
+    Markdown,
+    /// Like Markdown, but with the emphasis understood by chat apps like
+    /// Slack and Discord, which don't agree on Markdown.
+    Chat,
+}
+impl TextFormat {
+    pub const ALL: [Self; 3] = [Self::Plain, Self::Markdown, Self::Chat];
+}
+impl fmt::Display for TextFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain => write!(f, "plain"),
+            Self::Markdown => write!(f, "markdown"),
+            Self::Chat => write!(f, "chat"),
+        }
+    }
+}
+
+/// Escapes the characters that have a meaning in Markdown.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '`' | '~' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Randomization {
+    /// The title of the result: the scenario and scene names.
+    pub fn title(&self) -> String {
+        format!("{} - {}", self.scenario.name, self.scene)
+    }
+
+    /// Writes out the result as text in `format`, one line per group.
+    pub fn format(&self, format: TextFormat) -> String {
+        let title = self.title();
+        let (title, lines) = match format {
+            TextFormat::Plain => (
+                title,
+                self.messages.iter().map(ToString::to_string).collect_vec(),
+            ),
+            TextFormat::Markdown => (
+                format!("**{}**", escape_markdown(&title)),
+                self.messages
+                    .iter()
+                    .map(|msg| {
+                        let escaped = RandomizedMsg {
+                            message: escape_markdown(&msg.message),
+                            names: msg.names.clone(),
+                        };
+                        format!(
+                            "- {}",
+                            escaped.render(|name| format!("**{}**", escape_markdown(name)))
+                        )
+                    })
+                    .collect(),
+            ),
+            // Slack makes *text* bold, Discord italic; both stand out.
+            // Neither reliably understands backslash escapes.
+            TextFormat::Chat => (
+                format!("*{title}*"),
+                self.messages
+                    .iter()
+                    .map(|msg| format!("• {}", msg.render(|name| format!("*{name}*"))))
+                    .collect(),
+            ),
+        };
+        let separator = if format == TextFormat::Markdown {
+            "\n\n"
+        } else {
+            "\n"
+        };
+        format!("{title}{separator}{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ScenarioMeta, ScenarioSource};
+
+    fn result() -> Randomization {
+        let mut scenario = ScenarioMeta::from_filename("trip.json", ScenarioSource::Local);
+        scenario.name = "Trip [2]".to_string();
+        Randomization {
+            scenario,
+            scene: "Beach".to_string(),
+            lang: None,
+            messages: vec![
+                RandomizedMsg {
+                    message: "%1$s *meets* %2$s".to_string(),
+                    names: vec!["A_b".to_string(), "C*d".to_string()],
+                },
+                RandomizedMsg {
+                    message: "%1$s and %2$s".to_string(),
+                    names: vec!["X".to_string(), "Y".to_string(), "Z".to_string()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn plain_is_unchanged() {
+        assert_eq!(
+            result().format(TextFormat::Plain),
+            "Trip [2] - Beach\nA_b *meets* C*d\nX and Y & Z"
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_names_and_messages() {
+        assert_eq!(
+            result().format(TextFormat::Markdown),
+            "**Trip \\[2\\] - Beach**\n\n\
+             - **A\\_b** \\*meets\\* **C\\*d**\n\
+             - **X** and **Y** & **Z**"
+        );
+    }
+
+    #[test]
+    fn chat_only_adds_emphasis() {
+        assert_eq!(
+            result().format(TextFormat::Chat),
+            "*Trip [2] - Beach*\n• *A_b* *meets* *C*d*\n• *X* and *Y* & *Z*"
+        );
+    }
+}
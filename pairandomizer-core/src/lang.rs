@@ -0,0 +1,80 @@
+//! Matching scenario languages against the languages a user prefers.
+
+use itertools::Itertools;
+use unic_langid::LanguageIdentifier;
+
+/// The language tried after all of the user's own, as it's the one most
+/// users can read if none of theirs is available. The interface falls back
+/// to it as well.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Builds the list of languages to look for, most preferred first.
+///
+/// Each language is followed by its base language, and the fallback language
+/// comes last, so `[de-AT, fr-CA]` becomes `[de-AT, de, fr-CA, fr, en]`.
+pub fn fallback_chain(preferred: &[LanguageIdentifier]) -> Vec<LanguageIdentifier> {
+    let fallback = FALLBACK_LANGUAGE.parse::<LanguageIdentifier>().ok();
+    preferred
+        .iter()
+        .flat_map(|lang| {
+            let base = LanguageIdentifier::from_parts(lang.language, None, None, &[]);
+            [lang.clone(), base]
+        })
+        .chain(fallback)
+        .unique()
+        .collect()
+}
+
+/// The position of the first language in `chain` that `lang` falls under,
+/// or None if it matches none of them.
+///
+/// More general languages in the chain match more specific ones, so a "de"
+/// entry matches a "de-AT" scenario, but not the other way around.
+pub fn match_rank(chain: &[LanguageIdentifier], lang: &str) -> Option<usize> {
+    let lang = lang.parse::<LanguageIdentifier>().ok()?;
+    chain
+        .iter()
+        .position(|wanted| wanted.matches(&lang, true, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn langs(tags: &[&str]) -> Vec<LanguageIdentifier> {
+        tags.iter().map(|tag| tag.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn chain_adds_base_and_fallback_languages() {
+        assert_eq!(
+            fallback_chain(&langs(&["de-AT"])),
+            langs(&["de-AT", "de", "en"])
+        );
+        assert_eq!(
+            fallback_chain(&langs(&["de-AT", "fr-CA"])),
+            langs(&["de-AT", "de", "fr-CA", "fr", "en"])
+        );
+        assert_eq!(
+            fallback_chain(&langs(&["en-US", "de"])),
+            langs(&["en-US", "en", "de"])
+        );
+        assert_eq!(fallback_chain(&[]), langs(&["en"]));
+    }
+
+    #[test]
+    fn rank_prefers_earlier_languages() {
+        let chain = fallback_chain(&langs(&["de-AT"]));
+        assert_eq!(match_rank(&chain, "de-AT"), Some(0));
+        assert_eq!(match_rank(&chain, "de"), Some(1));
+        assert_eq!(match_rank(&chain, "de-CH"), Some(1));
+        assert_eq!(match_rank(&chain, "en-GB"), Some(2));
+        assert_eq!(match_rank(&chain, "fr"), None);
+        assert_eq!(match_rank(&chain, "not a language"), None);
+
+        let chain = fallback_chain(&langs(&["de-AT", "fr-CA"]));
+        assert_eq!(match_rank(&chain, "fr-CA"), Some(2));
+        assert_eq!(match_rank(&chain, "fr"), Some(3));
+        assert_eq!(match_rank(&chain, "en"), Some(4));
+    }
+}
@@ -0,0 +1,507 @@
+//! The platform independent data model of the Pairandomizer: index and
+//! scenario files, validating them and randomizing pairs.
+
+use core::fmt;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use rand::prelude::SliceRandom;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+mod format;
+mod lang;
+mod link;
+mod randomize;
+mod stats;
+mod translation;
+mod validate;
+
+pub use format::TextFormat;
+pub use lang::{fallback_chain, match_rank, FALLBACK_LANGUAGE};
+pub use link::{ResultLink, RosterLink, Unavailable};
+pub use randomize::{
+    parse_names, rng_from_seed, PickedScene, Randomization, RandomizeError, RandomizedMsg,
+    DEFAULT_GROUP_SIZE,
+};
+pub use stats::Statistics;
+pub use translation::{ScenarioTranslation, SceneTranslation};
+pub use validate::{Issue, IssueKind, Severity, TemplateKind};
+
+/// Name of the index file, next to which the scenario files are stored.
+pub const INDEX_FILENAME: &str = "index.json";
+
+/// Version of the format index and scenario files are written in.
+///
+/// Files with a newer minor version are still read (unknown additions are
+/// ignored), while a different major version is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+impl FormatVersion {
+    /// The newest format this version of the app understands.
+    pub const CURRENT: Self = Self { major: 1, minor: 0 };
+
+    /// Fails if files in this format can't be read.
+    pub fn check_supported(self) -> Result<(), UnsupportedVersion> {
+        if self.major == Self::CURRENT.major {
+            Ok(())
+        } else {
+            Err(UnsupportedVersion {
+                found: self,
+                supported: Self::CURRENT,
+            })
+        }
+    }
+}
+/// A file is in a format version that can't be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedVersion {
+    pub found: FormatVersion,
+    pub supported: FormatVersion,
+}
+impl UnsupportedVersion {
+    /// Whether the file is newer than what is supported, so updating the
+    /// app would help.
+    pub fn needs_update(&self) -> bool {
+        self.found > self.supported
+    }
+}
+impl fmt::Display for UnsupportedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.needs_update() {
+            write!(
+                f,
+                "format version {} is newer than the supported version {}, please update the app",
+                self.found, self.supported
+            )
+        } else {
+            write!(
+                f,
+                "format version {} is no longer supported (expected {})",
+                self.found, self.supported
+            )
+        }
+    }
+}
+impl std::error::Error for UnsupportedVersion {}
+
+impl Default for FormatVersion {
+    /// Files written before versioning was introduced are version 1.0.
+    fn default() -> Self {
+        Self { major: 1, minor: 0 }
+    }
+}
+impl fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+impl FromStr for FormatVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+        Ok(Self {
+            major: major
+                .parse()
+                .with_context(|| format!("invalid format version: {s}"))?,
+            minor: minor
+                .parse()
+                .with_context(|| format!("invalid format version: {s}"))?,
+        })
+    }
+}
+impl Serialize for FormatVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for FormatVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parses an index or scenario file.
+///
+/// The format version is checked before anything else, so files made for a
+/// newer app fail with a helpful message instead of an obscure parse error.
+pub fn parse_versioned<T: DeserializeOwned>(json: &str) -> Result<T> {
+    #[derive(Deserialize)]
+    struct Versioned {
+        #[serde(default)]
+        format_version: FormatVersion,
+    }
+    let Versioned { format_version } = serde_json::from_str(json)?;
+    format_version.check_supported()?;
+    Ok(serde_json::from_str(json)?)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    #[serde(default)]
+    pub format_version: FormatVersion,
+    pub server_name: String,
+    pub comment: String,
+    pub scenarios: Vec<ScenarioMeta>,
+    /// Scenarios that are only picked at random if the user opted in.
+    ///
+    /// May be a single entry, a list or null.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub beta: Vec<ScenarioMeta>,
+}
+
+impl Index {
+    /// All scenarios listed in the index, with beta scenarios marked as such.
+    pub fn all_scenarios(&self) -> impl Iterator<Item = ScenarioMeta> + '_ {
+        let beta = self.beta.iter().map(|meta| ScenarioMeta {
+            beta: true,
+            ..meta.clone()
+        });
+        self.scenarios.iter().cloned().chain(beta)
+    }
+}
+
+/// Deserializes either a single value, a list of values or null into a list.
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+    Ok(match Option::<OneOrMany<T>>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(value)) => vec![value],
+        Some(OneOrMany::Many(values)) => values,
+    })
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadedIndex {
+    pub inner: Index,
+    pub scenarios: Vec<(ScenarioMeta, Scenario)>,
+    /// ETags the server sent for the loaded files, keyed by their filename.
+    ///
+    /// Only used when loading from a server.
+    #[serde(default)]
+    pub etags: HashMap<String, String>,
+    /// Problems found in the loaded files, along with the file's name.
+    ///
+    /// Scenes and scenarios that can't be used because of them have already
+    /// been left out.
+    #[serde(default)]
+    pub issues: Vec<(String, Issue)>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioMeta {
+    pub name: String,
+    pub lang: Option<String>,
+    /// Further languages the scenario is translated into, besides `lang`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    pub filename: String,
+    /// Content hash or version of the scenario file.
+    ///
+    /// If it is set and matches the one of a previously loaded copy, that
+    /// copy is reused without asking the server.
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Whether this is a beta scenario; set for all entries of [`Index::beta`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub beta: bool,
+    /// Only kept track of by the app, so it's left out of index files.
+    #[serde(default, skip_serializing_if = "ScenarioSource::is_server")]
+    pub source: ScenarioSource,
+}
+impl ScenarioMeta {
+    /// Guesses the metadata of a scenario file that isn't listed in an index.
+    ///
+    /// A `name_xx.json` file gets the name "name" and the language "xx".
+    pub fn from_filename(filename: &str, source: ScenarioSource) -> Self {
+        let stem = filename.strip_suffix(".json").unwrap_or(filename);
+        let (name, lang) = match stem.rsplit_once('_') {
+            Some((name, lang))
+                if lang.len() == 2 && lang.chars().all(|c| c.is_ascii_lowercase()) =>
+            {
+                (name, Some(lang.to_string()))
+            }
+            _ => (stem, None),
+        };
+        Self {
+            name: name.to_string(),
+            lang,
+            languages: Vec::new(),
+            filename: filename.to_string(),
+            hash: None,
+            beta: false,
+            source,
+        }
+    }
+}
+/// Where a scenario comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioSource {
+    /// Listed in a server's index.
+    #[default]
+    Server,
+    /// Added on this device, e.g. by importing a file.
+    Local,
+}
+impl ScenarioSource {
+    pub fn is_server(&self) -> bool {
+        *self == Self::Server
+    }
+}
+
+/// Restrictions for [`LoadedIndex::pick_scenario`] and
+/// [`LoadedIndex::randomize`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PickOptions {
+    /// Always pick the scenario at this position, if it exists.
+    pub scenario_index: Option<usize>,
+    /// Always pick the scene at this position of the scenario, if it exists.
+    pub scene_index: Option<usize>,
+    /// Pick from all languages instead of preferring the user's one.
+    pub ignore_language: bool,
+    /// Whether beta scenarios may be picked.
+    pub enable_beta: bool,
+}
+
+impl LoadedIndex {
+    /// Starts a loaded index with no scenarios, recording the index's issues.
+    pub fn new(inner: Index) -> Self {
+        let issues = inner
+            .validate()
+            .into_iter()
+            .map(|issue| (INDEX_FILENAME.to_string(), issue))
+            .collect();
+        Self {
+            inner,
+            scenarios: Vec::new(),
+            etags: HashMap::new(),
+            issues,
+        }
+    }
+    /// An index without a server, to hold local scenarios when no server
+    /// index is available.
+    pub fn local_only() -> Self {
+        Self {
+            inner: Index {
+                format_version: FormatVersion::CURRENT,
+                server_name: "Local".to_string(),
+                comment: String::new(),
+                scenarios: Vec::new(),
+                beta: Vec::new(),
+            },
+            scenarios: Vec::new(),
+            etags: HashMap::new(),
+            issues: Vec::new(),
+        }
+    }
+    /// Validates a freshly loaded scenario and adds what's usable of it.
+    ///
+    /// Scenes without messages are left out, as are scenarios that end up
+    /// without any scenes.
+    pub fn add_scenario(&mut self, mut meta: ScenarioMeta, mut scenario: Scenario) {
+        meta.add_languages_of(&scenario);
+        self.issues.extend(
+            scenario
+                .validate()
+                .into_iter()
+                .map(|issue| (meta.filename.clone(), issue)),
+        );
+        scenario.scenes.retain(|scene| !scene.messages.is_empty());
+        if !scenario.scenes.is_empty() {
+            self.scenarios.push((meta, scenario));
+        }
+    }
+    /// Picks a random scenario that can be used with `names` names.
+    ///
+    /// If options.scenario_index is Some and the stored index exists, that
+    /// scenario will be returned.
+    ///
+    /// Beta scenarios are only considered if options.enable_beta is true.
+    ///
+    /// If options.ignore_language is true, it's picked out of all available
+    /// scenarios known to the index.
+    ///
+    /// When it is false, it's picked out of the scenarios offered in the best
+    /// matching language, `languages` being a list like the one built by
+    /// [`fallback_chain`]. Translations count as offering the scenario in
+    /// their language. If no scenario matches any of the languages, it
+    /// behaves as if ignore_language is true.
+    ///
+    /// Fails if there's no scenario left to pick from, or none of them can
+    /// be used with that few names.
+    pub fn pick_scenario<R: rand::Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        options: &PickOptions,
+        languages: &[LanguageIdentifier],
+        names: usize,
+    ) -> Result<&(ScenarioMeta, Scenario), RandomizeError> {
+        let candidates = self.candidates(options, languages);
+        let fitting = candidates
+            .iter()
+            .filter(|(_, scenario)| scenario.min_names() <= names)
+            .collect_vec();
+        match fitting.choose(rng) {
+            Some(scenario) => Ok(scenario),
+            None if candidates.is_empty() => Err(RandomizeError::NoScenarios),
+            None => Err(RandomizeError::NotEnoughNames(
+                candidates
+                    .iter()
+                    .map(|(_, scenario)| scenario.min_names())
+                    .min()
+                    .unwrap_or(2),
+            )),
+        }
+    }
+    /// The scenarios [`LoadedIndex::pick_scenario`] picks from, before
+    /// leaving out those needing more names.
+    fn candidates(
+        &self,
+        options: &PickOptions,
+        languages: &[LanguageIdentifier],
+    ) -> Vec<&(ScenarioMeta, Scenario)> {
+        if let Some(index) = options.scenario_index {
+            if let Some(scenario) = self.scenarios.get(index) {
+                return vec![scenario];
+            }
+        }
+        let scenarios = self
+            .scenarios
+            .iter()
+            .filter(|(s, _)| options.enable_beta || !s.beta)
+            .collect_vec();
+        let ranks = scenarios
+            .iter()
+            .map(|(s, _)| s.best_language(languages).map(|(rank, _)| rank))
+            .collect_vec();
+        match ranks.iter().flatten().min() {
+            Some(best) if !options.ignore_language => scenarios
+                .iter()
+                .zip(&ranks)
+                .filter(|(_, rank)| rank.as_ref() == Some(best))
+                .map(|(scenario, _)| *scenario)
+                .collect_vec(),
+            _ => scenarios,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub format_version: FormatVersion,
+    pub scenes: Vec<Scene>,
+    /// Messages for a single name, used by scenes without their own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solo_messages: Vec<String>,
+    /// Translations of [`Scenario::solo_messages`], keyed by language.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, ScenarioTranslation>,
+}
+impl Scenario {
+    /// The fewest names any of the scenes can be randomized with.
+    pub fn min_names(&self) -> usize {
+        self.scenes
+            .iter()
+            .map(|scene| self.scene_min_names(scene))
+            .min()
+            .unwrap_or(2)
+    }
+    /// The fewest names `scene` of this scenario can be randomized with,
+    /// counting the scenario's solo messages.
+    pub fn scene_min_names(&self, scene: &Scene) -> usize {
+        if self.solo_messages.is_empty() {
+            scene.min_names()
+        } else {
+            1
+        }
+    }
+}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    pub messages: Vec<String>,
+    /// Messages for groups of three, using `%3$s` for the third name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triple_messages: Vec<String>,
+    /// Messages for a single name left over.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solo_messages: Vec<String>,
+    /// Translations of the scene, keyed by language.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, SceneTranslation>,
+}
+impl Scene {
+    /// The message templates to use for a group of `group_size` names.
+    ///
+    /// A single name uses the solo messages and groups of three use the
+    /// triple messages if there are any; all other groups use the regular
+    /// messages.
+    pub fn templates(&self, group_size: usize) -> &[String] {
+        match group_size {
+            1 => &self.solo_messages,
+            3 if !self.triple_messages.is_empty() => &self.triple_messages,
+            _ => &self.messages,
+        }
+    }
+    /// The fewest names this scene can be randomized with.
+    pub fn min_names(&self) -> usize {
+        if self.solo_messages.is_empty() {
+            2
+        } else {
+            1
+        }
+    }
+}
+/// A placeholder found in a message template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder<'a> {
+    /// `%n$s`, which is replaced by the n-th name.
+    Name(usize),
+    /// Something that looks like a placeholder, but isn't a valid one.
+    Invalid(&'a str),
+}
+
+/// Finds all placeholders in a message template.
+pub fn placeholders(template: &str) -> Vec<Placeholder<'_>> {
+    let mut result = Vec::new();
+    for (start, _) in template.match_indices('%') {
+        let rest = &template[start + 1..];
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if rest[digits..].starts_with("$s") {
+            match rest[..digits].parse() {
+                Ok(0) | Err(_) => {}
+                Ok(n) => {
+                    result.push(Placeholder::Name(n));
+                    continue;
+                }
+            }
+        }
+        if rest.starts_with(|c: char| c.is_ascii_digit() || c == '$' || c == 's') {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            result.push(Placeholder::Invalid(&template[start..start + 1 + end]));
+        }
+    }
+    result
+}
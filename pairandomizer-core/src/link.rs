@@ -0,0 +1,507 @@
+//! Links that carry a result, so opening them shows the same result again,
+//! or a list of names to import.
+//!
+//! They are meant for the fragment of the app's URL, like
+//! `#result?s=42&f=urlaub_en.json&c=Beach&n=Alice,Bob`, and only use
+//! characters that are safe there.
+
+use core::fmt;
+
+use anyhow::{anyhow, bail, Context, Result};
+use itertools::Itertools;
+
+use crate::{LoadedIndex, PickedScene, Randomization, DEFAULT_GROUP_SIZE};
+
+/// Escapes everything but unreserved URL characters as `%XX`.
+pub(crate) fn encode_component(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Undoes [`encode_component`], also accepting `+` for a space.
+pub(crate) fn decode_component(text: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .with_context(|| format!("incomplete escape in {text:?}"))?;
+                // `from_str_radix` would also take a sign, as in `%+1`
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    bail!(
+                        "invalid escape %{} in {text:?}",
+                        String::from_utf8_lossy(hex)
+                    );
+                }
+                let hex = std::str::from_utf8(hex).expect("hex digits are ASCII");
+                bytes.push(u8::from_str_radix(hex, 16).expect("checked hex digits"));
+                rest = &rest[2..];
+            }
+            b'+' => bytes.push(b' '),
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).with_context(|| format!("invalid text in {text:?}"))
+}
+
+/// Splits a fragment like `kind?a=1&b=2` into the kind and its still
+/// encoded parameters.
+pub(crate) fn parse_fragment(fragment: &str) -> (&str, Vec<(&str, &str)>) {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let (kind, query) = fragment.split_once('?').unwrap_or((fragment, ""));
+    let params = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| param.split_once('=').unwrap_or((param, "")))
+        .collect();
+    (kind, params)
+}
+
+/// Encodes a list of names, separated by commas.
+pub(crate) fn encode_names(names: &[String]) -> String {
+    names.iter().map(|name| encode_component(name)).join(",")
+}
+
+/// Decodes a list of names written by [`encode_names`].
+pub(crate) fn decode_names(text: &str) -> Result<Vec<String>> {
+    text.split(',')
+        .filter(|name| !name.is_empty())
+        .map(decode_component)
+        .collect()
+}
+
+/// What a link points to isn't among the loaded scenarios.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unavailable {
+    /// The scenario with this filename.
+    Scenario(String),
+    /// A scene of the scenario with the filename.
+    Scene { filename: String, scene: String },
+}
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scenario(filename) => write!(f, "the scenario {filename} is not available"),
+            Self::Scene { filename, scene } => {
+                write!(f, "the scene \"{scene}\" of {filename} is not available")
+            }
+        }
+    }
+}
+impl std::error::Error for Unavailable {}
+
+/// Everything needed to randomize a result again.
+///
+/// The result is only the same if the scene's messages are still the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultLink {
+    pub seed: u64,
+    /// Filename of the scenario, which identifies it.
+    pub filename: String,
+    /// Name of the scene, in `lang`.
+    pub scene: String,
+    pub lang: Option<String>,
+    pub group_size: usize,
+    pub names: Vec<String>,
+}
+
+impl ResultLink {
+    /// The kind of fragment a result link is.
+    const KIND: &'static str = "result";
+
+    /// A link to `result`, assuming it was randomized by
+    /// [`PickedScene::randomize_seeded`] with `seed` and `names`.
+    pub fn new(seed: u64, result: &Randomization, names: &[String], group_size: usize) -> Self {
+        Self {
+            seed,
+            filename: result.scenario.filename.clone(),
+            scene: result.scene.clone(),
+            lang: result.lang.clone(),
+            group_size,
+            names: names.to_vec(),
+        }
+    }
+
+    /// Writes the link as a URL fragment, without the leading `#`.
+    pub fn to_fragment(&self) -> String {
+        let mut params = vec![
+            format!("s={}", self.seed),
+            format!("f={}", encode_component(&self.filename)),
+            format!("c={}", encode_component(&self.scene)),
+        ];
+        if let Some(lang) = &self.lang {
+            params.push(format!("l={}", encode_component(lang)));
+        }
+        if self.group_size != DEFAULT_GROUP_SIZE {
+            params.push(format!("g={}", self.group_size));
+        }
+        params.push(format!("n={}", encode_names(&self.names)));
+        format!("{}?{}", Self::KIND, params.join("&"))
+    }
+
+    /// Reads a link written by [`ResultLink::to_fragment`], with or without
+    /// the leading `#`.
+    ///
+    /// Returns None if the fragment isn't a result link at all.
+    pub fn from_fragment(fragment: &str) -> Option<Result<Self>> {
+        let (kind, params) = parse_fragment(fragment);
+        (kind == Self::KIND).then(|| Self::from_params(&params))
+    }
+
+    fn from_params(params: &[(&str, &str)]) -> Result<Self> {
+        let mut link = Self {
+            seed: 0,
+            filename: String::new(),
+            scene: String::new(),
+            lang: None,
+            group_size: DEFAULT_GROUP_SIZE,
+            names: Vec::new(),
+        };
+        let mut seed = None;
+        for &(key, value) in params {
+            match key {
+                "s" => {
+                    seed = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("invalid seed {value:?}"))?,
+                    )
+                }
+                "f" => link.filename = decode_component(value)?,
+                "c" => link.scene = decode_component(value)?,
+                "l" => link.lang = Some(decode_component(value)?),
+                "g" => {
+                    link.group_size = value
+                        .parse()
+                        .with_context(|| format!("invalid group size {value:?}"))?
+                }
+                "n" => link.names = decode_names(value)?,
+                // Parameters of newer versions
+                _ => {}
+            }
+        }
+        link.seed = seed.ok_or_else(|| anyhow!("the link has no seed"))?;
+        if link.filename.is_empty() || link.scene.is_empty() {
+            bail!("the link doesn't say which scene to use");
+        }
+        Ok(link)
+    }
+
+    /// Randomizes the result the link is for again.
+    ///
+    /// Fails if the scene can't be found or the names can't be randomized
+    /// with it.
+    pub fn regenerate(&self, index: &LoadedIndex) -> Result<(PickedScene, Randomization)> {
+        let picked = index
+            .find_scene(&self.filename, &self.scene, self.lang.as_deref())
+            .ok_or_else(|| Unavailable::Scene {
+                filename: self.filename.clone(),
+                scene: self.scene.clone(),
+            })?;
+        let result = picked.randomize_seeded(self.seed, &self.names, self.group_size)?;
+        Ok((picked, result))
+    }
+
+    /// Whether regenerating from this link gives exactly `result`.
+    pub fn reproduces(&self, picked: &PickedScene, result: &Randomization) -> bool {
+        picked
+            .randomize_seeded(self.seed, &self.names, self.group_size)
+            .is_ok_and(|regenerated| regenerated == *result)
+    }
+}
+
+/// A list of names to import, optionally with the scenario and scene to
+/// restrict picking to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosterLink {
+    pub names: Vec<String>,
+    /// Filename of the selected scenario.
+    pub scenario: Option<String>,
+    /// Name of the selected scene, only set along with `scenario`.
+    pub scene: Option<String>,
+}
+
+impl RosterLink {
+    /// The kind of fragment a roster link is.
+    const KIND: &'static str = "roster";
+
+    /// A link to `names`, along with the scenario and scene at the given
+    /// positions in `index`, if any.
+    pub fn new(
+        names: &[String],
+        index: Option<&LoadedIndex>,
+        scenario_index: Option<usize>,
+        scene_index: Option<usize>,
+    ) -> Self {
+        let selected = index
+            .zip(scenario_index)
+            .and_then(|(index, ix)| index.scenarios.get(ix));
+        Self {
+            names: names.to_vec(),
+            scenario: selected.map(|(meta, _)| meta.filename.clone()),
+            scene: selected
+                .zip(scene_index)
+                .and_then(|((_, scenario), ix)| scenario.scenes.get(ix))
+                .map(|scene| scene.name.clone()),
+        }
+    }
+
+    /// Writes the link as a URL fragment, without the leading `#`.
+    pub fn to_fragment(&self) -> String {
+        let mut params = vec![format!("n={}", encode_names(&self.names))];
+        if let Some(scenario) = &self.scenario {
+            params.push(format!("f={}", encode_component(scenario)));
+            if let Some(scene) = &self.scene {
+                params.push(format!("c={}", encode_component(scene)));
+            }
+        }
+        format!("{}?{}", Self::KIND, params.join("&"))
+    }
+
+    /// Reads a link written by [`RosterLink::to_fragment`], with or without
+    /// the leading `#`.
+    ///
+    /// Returns None if the fragment isn't a roster link at all.
+    pub fn from_fragment(fragment: &str) -> Option<Result<Self>> {
+        let (kind, params) = parse_fragment(fragment);
+        (kind == Self::KIND).then(|| Self::from_params(&params))
+    }
+
+    fn from_params(params: &[(&str, &str)]) -> Result<Self> {
+        let mut link = Self {
+            names: Vec::new(),
+            scenario: None,
+            scene: None,
+        };
+        for &(key, value) in params {
+            match key {
+                "n" => link.names = decode_names(value)?,
+                "f" => link.scenario = Some(decode_component(value)?),
+                "c" => link.scene = Some(decode_component(value)?),
+                // Parameters of newer versions
+                _ => {}
+            }
+        }
+        if link.names.is_empty() {
+            bail!("the link has no names");
+        }
+        if link.scenario.is_none() {
+            link.scene = None;
+        }
+        Ok(link)
+    }
+
+    /// The positions of the linked scenario and scene in `index`, as used by
+    /// the settings.
+    ///
+    /// Fails if the link has a scenario or scene that isn't in `index`.
+    pub fn selection(&self, index: &LoadedIndex) -> Result<(Option<usize>, Option<usize>)> {
+        let Some(filename) = &self.scenario else {
+            return Ok((None, None));
+        };
+        let (scenario_index, (_, scenario)) = index
+            .scenarios
+            .iter()
+            .enumerate()
+            .find(|(_, (meta, _))| meta.filename == *filename)
+            .ok_or_else(|| Unavailable::Scenario(filename.clone()))?;
+        let Some(scene) = &self.scene else {
+            return Ok((Some(scenario_index), None));
+        };
+        let scene_index = scenario
+            .scenes
+            .iter()
+            .position(|s| s.name == *scene)
+            .ok_or_else(|| Unavailable::Scene {
+                filename: filename.clone(),
+                scene: scene.clone(),
+            })?;
+        Ok((Some(scenario_index), Some(scene_index)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fallback_chain, rng_from_seed, PickOptions, Scenario, ScenarioMeta, ScenarioSource, Scene,
+        SceneTranslation,
+    };
+
+    fn names() -> Vec<String> {
+        ["Al,ice", "Bob & Carol", "100%", "1+1", "Zoë Müller", "名前"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn result_link_round_trip() {
+        let link = ResultLink {
+            seed: u64::MAX,
+            filename: "urlaub_de.json".to_string(),
+            scene: "Strand & Meer?".to_string(),
+            lang: Some("de-AT".to_string()),
+            group_size: 3,
+            names: names(),
+        };
+        let fragment = link.to_fragment();
+        assert!(fragment
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-._~%?&=,".contains(&byte)));
+        assert_eq!(ResultLink::from_fragment(&fragment).unwrap().unwrap(), link);
+        assert_eq!(
+            ResultLink::from_fragment(&format!("#{fragment}"))
+                .unwrap()
+                .unwrap(),
+            link
+        );
+    }
+
+    #[test]
+    fn result_link_defaults() {
+        let link = ResultLink::from_fragment("result?s=1&f=a.json&c=B&n=x,y")
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.lang, None);
+        assert_eq!(link.group_size, DEFAULT_GROUP_SIZE);
+        assert_eq!(link.to_fragment(), "result?s=1&f=a.json&c=B&n=x,y");
+    }
+
+    #[test]
+    fn result_link_rejects_incomplete() {
+        assert!(ResultLink::from_fragment("other?s=1").is_none());
+        assert!(ResultLink::from_fragment("result?f=a.json&c=B")
+            .unwrap()
+            .is_err());
+        assert!(ResultLink::from_fragment("result?s=x&f=a.json&c=B")
+            .unwrap()
+            .is_err());
+        assert!(ResultLink::from_fragment("result?s=1&f=a.json")
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn roster_link_round_trip() {
+        let link = RosterLink {
+            names: names(),
+            scenario: Some("stadt ausflug.json".to_string()),
+            scene: Some("Café & Bar".to_string()),
+        };
+        let fragment = link.to_fragment();
+        assert_eq!(RosterLink::from_fragment(&fragment).unwrap().unwrap(), link);
+
+        let link = RosterLink {
+            names: names(),
+            scenario: None,
+            scene: None,
+        };
+        let fragment = link.to_fragment();
+        assert_eq!(RosterLink::from_fragment(&fragment).unwrap().unwrap(), link);
+    }
+
+    #[test]
+    fn roster_link_rejects_incomplete() {
+        assert!(RosterLink::from_fragment("result?n=a").is_none());
+        assert!(RosterLink::from_fragment("roster?f=a.json")
+            .unwrap()
+            .is_err());
+        let link = RosterLink::from_fragment("roster?n=a&c=B")
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.scene, None);
+    }
+
+    fn index() -> LoadedIndex {
+        let messages = |text: &str| (1..=4).map(|n| format!("{text} {n}: %1$s, %2$s")).collect();
+        let scene = Scene {
+            name: "Beach".to_string(),
+            messages: messages("Swim"),
+            translations: [(
+                "de".to_string(),
+                SceneTranslation {
+                    name: Some("Strand".to_string()),
+                    messages: messages("Schwimmen"),
+                    ..SceneTranslation::default()
+                },
+            )]
+            .into(),
+            ..Scene::default()
+        };
+        let mut index = LoadedIndex::local_only();
+        index.add_scenario(
+            ScenarioMeta::from_filename("urlaub_en.json", ScenarioSource::Server),
+            Scenario {
+                scenes: vec![scene],
+                ..Scenario::default()
+            },
+        );
+        index
+    }
+
+    #[test]
+    fn result_link_regenerates_result() {
+        let index = index();
+        let languages = fallback_chain(&["de-AT".parse().unwrap()]);
+        let picked = index
+            .pick_scene(
+                &mut rng_from_seed(0),
+                &PickOptions::default(),
+                &languages,
+                names().len(),
+            )
+            .unwrap();
+        assert_eq!(picked.scene.name, "Strand");
+        let result = picked.randomize_seeded(1234, &names(), 2).unwrap();
+
+        let link = ResultLink::new(1234, &result, &names(), 2);
+        let link = ResultLink::from_fragment(&link.to_fragment())
+            .unwrap()
+            .unwrap();
+        let (regenerated_picked, regenerated) = link.regenerate(&index).unwrap();
+        assert_eq!(regenerated_picked, picked);
+        assert_eq!(regenerated, result);
+        assert!(link.reproduces(&picked, &result));
+
+        let other = ResultLink { seed: 4321, ..link };
+        assert!(!other.reproduces(&picked, &result));
+    }
+
+    #[test]
+    fn result_link_reports_missing_scene() {
+        let link = ResultLink::from_fragment("result?s=1&f=urlaub_en.json&c=Strand&n=a,b")
+            .unwrap()
+            .unwrap();
+        let error = link.regenerate(&index()).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Unavailable>(),
+            Some(Unavailable::Scene { scene, .. }) if scene == "Strand"
+        ));
+    }
+
+    #[test]
+    fn decode_plus_as_space() {
+        assert_eq!(decode_component("a+b%2Bc").unwrap(), "a b+c");
+    }
+
+    #[test]
+    fn decode_rejects_invalid_escapes() {
+        for text in ["%", "%4", "a%", "%zz", "%+1", "%-1", "%4g", "%C3"] {
+            assert!(decode_component(text).is_err(), "{text:?}");
+        }
+        assert!(RosterLink::from_fragment("roster?n=a%2").unwrap().is_err());
+        assert!(ResultLink::from_fragment("result?s=1&f=a.json&c=%+1&n=a")
+            .unwrap()
+            .is_err());
+    }
+}
@@ -0,0 +1,607 @@
+//! Randomizing names into groups and rendering their messages.
+
+use core::fmt;
+
+use std::borrow::Cow;
+
+use itertools::Itertools;
+use rand::{prelude::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+use crate::{LoadedIndex, PickOptions, Scenario, ScenarioMeta, Scene};
+
+/// How many names go into a group unless asked otherwise.
+pub const DEFAULT_GROUP_SIZE: usize = 2;
+
+/// Creates the random number generator used for seeded randomization.
+///
+/// The algorithm is fixed, so the same seed gives the same results on every
+/// platform and in every version using the same scenario data.
+pub fn rng_from_seed(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Splits a list of names, one per line, dropping empty lines and duplicates.
+pub fn parse_names(text: &str) -> Vec<String> {
+    text.trim()
+        .split('\n')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unique()
+        .map(str::to_string)
+        .collect()
+}
+
+impl Scene {
+    /// Shuffles the names into groups of `group_size` and picks a message for
+    /// each group.
+    ///
+    /// The last group may be smaller. A single name left over gets a solo
+    /// message if there are any, otherwise it joins the group before it.
+    pub fn randomize<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        names: &[String],
+        group_size: usize,
+    ) -> Result<Vec<RandomizedMsg>, RandomizeError> {
+        if names.len() < self.min_names() {
+            return Err(RandomizeError::NotEnoughNames(self.min_names()));
+        }
+        let mut names = names.iter().collect_vec();
+        names.shuffle(rng);
+        let mut groups = names
+            .chunks(group_size.max(2))
+            .map(<[_]>::to_vec)
+            .collect_vec();
+        if self.solo_messages.is_empty() {
+            if let [.., previous, last] = groups.as_mut_slice() {
+                if last.len() == 1 {
+                    previous.append(last);
+                }
+            }
+            groups.retain(|group| !group.is_empty());
+        }
+        groups
+            .into_iter()
+            .map(|group| {
+                let message = self
+                    .templates(group.len())
+                    .choose(rng)
+                    .ok_or_else(|| RandomizeError::NoMessages(self.name.clone()))?;
+                Ok(RandomizedMsg {
+                    message: message.clone(),
+                    names: group.into_iter().cloned().collect(),
+                })
+            })
+            .collect()
+    }
+    /// Like [`Scene::randomize`], but keeps the `fixed` groups as they are.
+    ///
+    /// Names in one of the fixed groups are left out of the randomization,
+    /// and the fixed groups come first in the result.
+    pub fn randomize_around<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        names: &[String],
+        group_size: usize,
+        fixed: &[RandomizedMsg],
+    ) -> Result<Vec<RandomizedMsg>, RandomizeError> {
+        let free = names
+            .iter()
+            .filter(|name| !fixed.iter().any(|group| group.names.contains(name)))
+            .cloned()
+            .collect_vec();
+        let mut messages = fixed.to_vec();
+        if !free.is_empty() {
+            messages.extend(self.randomize(rng, &free, group_size)?);
+        }
+        Ok(messages)
+    }
+}
+
+impl LoadedIndex {
+    /// The fewest names that can be randomized with `options` and
+    /// `languages`, out of the scenarios and scenes they let
+    /// [`LoadedIndex::pick_scene`] pick from.
+    pub fn min_names_for(&self, options: &PickOptions, languages: &[LanguageIdentifier]) -> usize {
+        if let Some((_, scenario)) = options
+            .scenario_index
+            .and_then(|index| self.scenarios.get(index))
+        {
+            return match options
+                .scene_index
+                .and_then(|index| scenario.scenes.get(index))
+            {
+                Some(scene) => scenario.scene_min_names(scene),
+                None => scenario.min_names(),
+            };
+        }
+        self.candidates(options, languages)
+            .iter()
+            .map(|(_, scenario)| scenario.min_names())
+            .min()
+            .unwrap_or(2)
+    }
+
+    /// Picks a scenario and scene according to `options` and randomizes the
+    /// names with it.
+    ///
+    /// Fails if there's no scenario to pick from, there aren't enough names
+    /// for the picked scene, or it has nothing to use.
+    pub fn randomize<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        options: &PickOptions,
+        languages: &[LanguageIdentifier],
+        names: &[String],
+        group_size: usize,
+    ) -> Result<Randomization, RandomizeError> {
+        self.pick_scene(rng, options, languages, names.len())?
+            .randomize(rng, names, group_size)
+    }
+
+    /// Picks a scenario and scene according to `options`, to randomize
+    /// `names` names with as often as needed.
+    ///
+    /// The scenario is used in the best matching language it is translated
+    /// into, even if the language was ignored for picking it. Scenes needing
+    /// more names are only picked if they were asked for.
+    ///
+    /// Fails if there's no scenario to pick from, it has no scenes, or
+    /// there aren't enough names for any of them.
+    pub fn pick_scene<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        options: &PickOptions,
+        languages: &[LanguageIdentifier],
+        names: usize,
+    ) -> Result<PickedScene, RandomizeError> {
+        let (meta, scenario) = self.pick_scenario(rng, options, languages, names)?;
+        let lang = meta
+            .best_language(languages)
+            .map(|(_, lang)| lang)
+            .or(meta.lang.as_deref());
+        let scenario = in_language(meta, scenario, lang);
+        let scene = match options
+            .scene_index
+            .and_then(|index| scenario.scenes.get(index))
+        {
+            Some(scene) => scene,
+            None if scenario.scenes.is_empty() => {
+                return Err(RandomizeError::NoScenes(meta.name.clone()))
+            }
+            None => scenario
+                .scenes
+                .iter()
+                .filter(|scene| scenario.scene_min_names(scene) <= names)
+                .collect_vec()
+                .choose(rng)
+                .copied()
+                .ok_or(RandomizeError::NotEnoughNames(scenario.min_names()))?,
+        };
+        Ok(PickedScene::new(meta, &scenario, scene, lang))
+    }
+
+    /// Finds the scene named `scene` in the scenario stored as `filename`,
+    /// as used for an earlier result in `lang`.
+    pub fn find_scene(
+        &self,
+        filename: &str,
+        scene: &str,
+        lang: Option<&str>,
+    ) -> Option<PickedScene> {
+        let (meta, scenario) = self
+            .scenarios
+            .iter()
+            .find(|(meta, _)| meta.filename == filename)?;
+        let scenario = in_language(meta, scenario, lang);
+        let found = scenario.scenes.iter().find(|s| s.name == scene)?;
+        Some(PickedScene::new(meta, &scenario, found, lang))
+    }
+}
+
+/// The scenario in `lang`, if that isn't its main language anyway.
+fn in_language<'a>(
+    meta: &ScenarioMeta,
+    scenario: &'a Scenario,
+    lang: Option<&str>,
+) -> Cow<'a, Scenario> {
+    match lang {
+        Some(lang) if meta.lang.as_deref() != Some(lang) => Cow::Owned(scenario.localized(lang)),
+        _ => Cow::Borrowed(scenario),
+    }
+}
+
+/// A scene picked by [`LoadedIndex::pick_scene`], ready to be used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickedScene {
+    pub scenario: ScenarioMeta,
+    /// The scene in the language it is used in, including the scenario's
+    /// solo messages if it has none of its own.
+    pub scene: Scene,
+    /// The language the scene is in, if known.
+    pub lang: Option<String>,
+}
+impl PickedScene {
+    fn new(meta: &ScenarioMeta, scenario: &Scenario, scene: &Scene, lang: Option<&str>) -> Self {
+        // Scenes without solo messages of their own use the scenario's
+        let scene = if scene.solo_messages.is_empty() {
+            Scene {
+                solo_messages: scenario.solo_messages.clone(),
+                ..scene.clone()
+            }
+        } else {
+            scene.clone()
+        };
+        Self {
+            scenario: meta.clone(),
+            scene,
+            lang: lang.map(str::to_string),
+        }
+    }
+    /// Randomizes the names with the scene.
+    ///
+    /// Fails if there aren't enough names or the scene has no messages.
+    pub fn randomize<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        names: &[String],
+        group_size: usize,
+    ) -> Result<Randomization, RandomizeError> {
+        Ok(Randomization {
+            scenario: self.scenario.clone(),
+            scene: self.scene.name.clone(),
+            lang: self.lang.clone(),
+            messages: self.scene.randomize(rng, names, group_size)?,
+        })
+    }
+    /// Randomizes the names with a generator seeded with `seed`, so a
+    /// [`crate::ResultLink`] can give the same result again.
+    pub fn randomize_seeded(
+        &self,
+        seed: u64,
+        names: &[String],
+        group_size: usize,
+    ) -> Result<Randomization, RandomizeError> {
+        self.randomize(&mut rng_from_seed(seed), names, group_size)
+    }
+}
+
+/// Why randomizing failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RandomizeError {
+    /// No scenario is available with the current settings.
+    NoScenarios,
+    /// The picked scenario, named here, has no scenes.
+    NoScenes(String),
+    /// The picked scene, named here, has no messages.
+    NoMessages(String),
+    /// There are fewer names than the given number the scene needs.
+    NotEnoughNames(usize),
+}
+impl fmt::Display for RandomizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoScenarios => write!(f, "there are no scenarios to pick from"),
+            Self::NoScenes(scenario) => write!(f, "the scenario \"{scenario}\" has no scenes"),
+            Self::NoMessages(scene) => write!(f, "the scene \"{scene}\" has no messages"),
+            Self::NotEnoughNames(1) => write!(f, "at least one name is needed"),
+            Self::NotEnoughNames(2) => write!(f, "at least two names are needed"),
+            Self::NotEnoughNames(needed) => write!(f, "at least {needed} names are needed"),
+        }
+    }
+}
+impl std::error::Error for RandomizeError {}
+
+/// The outcome of randomizing a list of names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Randomization {
+    pub scenario: ScenarioMeta,
+    /// Name of the scene that was used.
+    pub scene: String,
+    /// The language the scene and messages are in, if known.
+    #[serde(default)]
+    pub lang: Option<String>,
+    pub messages: Vec<RandomizedMsg>,
+}
+
+impl Randomization {
+    /// Picks another message from `scene` for the group at `row`, keeping the
+    /// names.
+    ///
+    /// The current message is only picked again if there is no other one.
+    pub fn reroll_message<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        scene: &Scene,
+        row: usize,
+    ) -> Result<(), RandomizeError> {
+        let Some(msg) = self.messages.get_mut(row) else {
+            return Ok(());
+        };
+        let templates = scene.templates(msg.names.len());
+        let others = templates
+            .iter()
+            .filter(|template| **template != msg.message)
+            .collect_vec();
+        let message = match others.choose(rng) {
+            Some(message) => *message,
+            None => templates
+                .choose(rng)
+                .ok_or_else(|| RandomizeError::NoMessages(scene.name.clone()))?,
+        };
+        msg.message = message.clone();
+        Ok(())
+    }
+    /// Gives the group at `row` a new partner by swapping one of its names
+    /// with one of another group that isn't `locked`, keeping all messages.
+    ///
+    /// Does nothing if there is no such group.
+    pub fn repair<R: Rng + ?Sized>(&mut self, rng: &mut R, row: usize, locked: &[usize]) {
+        let others = (0..self.messages.len())
+            .filter(|other| *other != row && !locked.contains(other))
+            .collect_vec();
+        let (Some(other), Some(group)) = (others.choose(rng), self.messages.get(row)) else {
+            return;
+        };
+        let own_ix = rng.gen_range(0..group.names.len());
+        let other_ix = rng.gen_range(0..self.messages[*other].names.len());
+        let name = self.messages[row].names[own_ix].clone();
+        let other_name = std::mem::replace(&mut self.messages[*other].names[other_ix], name);
+        self.messages[row].names[own_ix] = other_name;
+    }
+    /// Randomizes `names` with `scene` again, keeping the groups at the
+    /// `locked` rows as they are and where they are.
+    ///
+    /// On failure, the result is left unchanged.
+    pub fn reroll<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        scene: &Scene,
+        names: &[String],
+        group_size: usize,
+        locked: &[usize],
+    ) -> Result<(), RandomizeError> {
+        let fixed = locked
+            .iter()
+            .filter_map(|row| self.messages.get(*row).cloned())
+            .collect_vec();
+        let mut new = scene
+            .randomize_around(rng, names, group_size, &fixed)?
+            .into_iter()
+            .skip(fixed.len());
+        let mut messages = Vec::new();
+        for (row, msg) in self.messages.drain(..).enumerate() {
+            if locked.contains(&row) {
+                messages.push(msg);
+            } else {
+                messages.extend(new.next());
+            }
+        }
+        messages.extend(new);
+        self.messages = messages;
+        Ok(())
+    }
+}
+
+/// A group of names along with the message template picked for them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomizedMsg {
+    pub message: String,
+    pub names: Vec<String>,
+}
+
+impl RandomizedMsg {
+    /// Replaces each `%n$s` with the n-th name, passed through `name` first.
+    ///
+    /// If there are more names than placeholders, the last placeholder gets
+    /// all the remaining names, so pair messages still work for bigger groups.
+    pub fn render(&self, name: impl Fn(&str) -> String) -> String {
+        let last = (1..=self.names.len())
+            .rev()
+            .find(|n| self.message.contains(&format!("%{n}$s")))
+            .unwrap_or(self.names.len());
+        let mut message = self.message.clone();
+        for ix in 0..last.min(self.names.len()) {
+            let n = ix + 1;
+            let replacement = if n == last {
+                self.names[ix..].iter().map(|other| name(other)).join(" & ")
+            } else {
+                name(&self.names[ix])
+            };
+            message = message.replace(&format!("%{n}$s"), &replacement);
+        }
+        message
+    }
+}
+impl fmt::Display for RandomizedMsg {
+    /// Fills in the names as they are, see [`RandomizedMsg::render`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(str::to_string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(name: &str, solo: bool) -> Scene {
+        Scene {
+            name: name.to_string(),
+            messages: vec!["%1$s and %2$s".to_string()],
+            solo_messages: if solo {
+                vec!["%1$s alone".to_string()]
+            } else {
+                Vec::new()
+            },
+            ..Scene::default()
+        }
+    }
+
+    fn index(scenarios: Vec<(&str, Vec<Scene>)>) -> LoadedIndex {
+        let mut index = LoadedIndex::local_only();
+        for (filename, scenes) in scenarios {
+            index.add_scenario(
+                ScenarioMeta::from_filename(filename, crate::ScenarioSource::Local),
+                Scenario {
+                    scenes,
+                    ..Scenario::default()
+                },
+            );
+        }
+        index
+    }
+
+    #[test]
+    fn pick_only_what_fits_the_names() {
+        let index = index(vec![
+            ("pairs.json", vec![scene("Pair", false)]),
+            (
+                "mixed.json",
+                vec![scene("Pair", false), scene("Solo", true)],
+            ),
+        ]);
+        let options = PickOptions::default();
+        assert_eq!(index.min_names_for(&options, &[]), 1);
+        for seed in 0..20 {
+            let picked = index
+                .pick_scene(&mut rng_from_seed(seed), &options, &[], 1)
+                .unwrap();
+            assert_eq!(picked.scenario.filename, "mixed.json");
+            assert_eq!(picked.scene.name, "Solo");
+        }
+
+        let pairs = PickOptions {
+            scenario_index: Some(0),
+            ..PickOptions::default()
+        };
+        assert_eq!(index.min_names_for(&pairs, &[]), 2);
+        assert_eq!(
+            index.pick_scene(&mut rng_from_seed(0), &pairs, &[], 1),
+            Err(RandomizeError::NotEnoughNames(2))
+        );
+    }
+
+    #[test]
+    fn beta_scenarios_dont_count_unless_enabled() {
+        let mut index = index(vec![
+            ("pairs.json", vec![scene("Pair", false)]),
+            ("solo.json", vec![scene("Solo", true)]),
+        ]);
+        index.scenarios[1].0.beta = true;
+        let options = PickOptions::default();
+        assert_eq!(index.min_names_for(&options, &[]), 2);
+        assert_eq!(
+            index.pick_scene(&mut rng_from_seed(0), &options, &[], 1),
+            Err(RandomizeError::NotEnoughNames(2))
+        );
+        let options = PickOptions {
+            enable_beta: true,
+            ..PickOptions::default()
+        };
+        assert_eq!(index.min_names_for(&options, &[]), 1);
+        assert!(index
+            .pick_scene(&mut rng_from_seed(0), &options, &[], 1)
+            .is_ok());
+    }
+
+    fn names(count: usize) -> Vec<String> {
+        (1..=count).map(|n| format!("Name {n}")).collect()
+    }
+
+    fn group_sizes(messages: &[RandomizedMsg]) -> Vec<usize> {
+        messages.iter().map(|msg| msg.names.len()).collect()
+    }
+
+    #[test]
+    fn leftover_name_merges_without_solo_messages() {
+        let messages = scene("Pair", false)
+            .randomize(&mut rng_from_seed(1), &names(5), 2)
+            .unwrap();
+        assert_eq!(group_sizes(&messages), [2, 3]);
+        assert_eq!(messages[1].message, "%1$s and %2$s");
+    }
+
+    #[test]
+    fn leftover_name_goes_solo_with_solo_messages() {
+        let messages = scene("Solo", true)
+            .randomize(&mut rng_from_seed(1), &names(5), 2)
+            .unwrap();
+        assert_eq!(group_sizes(&messages), [2, 2, 1]);
+        assert_eq!(messages[2].message, "%1$s alone");
+        assert_eq!(
+            scene("Pair", false).randomize(&mut rng_from_seed(1), &names(1), 2),
+            Err(RandomizeError::NotEnoughNames(2))
+        );
+    }
+
+    #[test]
+    fn randomize_around_keeps_fixed_groups_first() {
+        let fixed = [RandomizedMsg {
+            message: "fixed".to_string(),
+            names: vec!["Name 4".to_string(), "Name 2".to_string()],
+        }];
+        let messages = scene("Pair", false)
+            .randomize_around(&mut rng_from_seed(2), &names(6), 2, &fixed)
+            .unwrap();
+        assert_eq!(messages[0], fixed[0]);
+        let mut rest = messages[1..]
+            .iter()
+            .flat_map(|msg| msg.names.clone())
+            .collect_vec();
+        rest.sort();
+        assert_eq!(rest, ["Name 1", "Name 3", "Name 5", "Name 6"]);
+    }
+
+    #[test]
+    fn reroll_keeps_locked_rows_in_place() {
+        let index = index(vec![("pairs.json", vec![scene("Pair", false)])]);
+        let names = names(7);
+        let scene = scene("Pair", false);
+        let mut result = index
+            .randomize(
+                &mut rng_from_seed(3),
+                &PickOptions::default(),
+                &[],
+                &names,
+                2,
+            )
+            .unwrap();
+        assert_eq!(group_sizes(&result.messages), [2, 2, 3]);
+        let locked = result.messages[1].clone();
+        for seed in 0..10 {
+            result
+                .reroll(&mut rng_from_seed(seed), &scene, &names, 2, &[1])
+                .unwrap();
+            assert_eq!(result.messages[1], locked);
+            assert_eq!(result.messages.len(), 3);
+            let mut all = result
+                .messages
+                .iter()
+                .flat_map(|msg| msg.names.clone())
+                .collect_vec();
+            all.sort();
+            assert_eq!(all, names);
+        }
+    }
+
+    #[test]
+    fn reroll_around_a_locked_last_row() {
+        let names = names(5);
+        let scene = scene("Solo", true);
+        let mut result = Randomization {
+            scenario: ScenarioMeta::from_filename("solo.json", crate::ScenarioSource::Local),
+            scene: scene.name.clone(),
+            lang: None,
+            messages: scene.randomize(&mut rng_from_seed(4), &names, 2).unwrap(),
+        };
+        let locked = result.messages[2].clone();
+        result
+            .reroll(&mut rng_from_seed(5), &scene, &names, 2, &[2])
+            .unwrap();
+        assert_eq!(group_sizes(&result.messages), [2, 2, 1]);
+        assert_eq!(result.messages[2], locked);
+    }
+}
@@ -0,0 +1,82 @@
+//! Statistics over earlier results, to check how fair the rotation is.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::Randomization;
+
+/// Counts of who was grouped with whom and what was used, over a number of
+/// results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// How many results were counted.
+    pub results: usize,
+    /// Everyone who appears in any of the results, sorted.
+    pub names: Vec<String>,
+    /// How often two names were in the same group, keyed by the two names in
+    /// sorted order. Pairs that never were are left out.
+    pub pairs: BTreeMap<(String, String), usize>,
+    /// How often each name ended up alone, for names that ever did.
+    pub solo: BTreeMap<String, usize>,
+    /// How often each scenario was used, by name, most used first.
+    pub scenarios: Vec<(String, usize)>,
+    /// How often each scene was used, by scenario and scene name, most used
+    /// first.
+    pub scenes: Vec<((String, String), usize)>,
+}
+
+impl Statistics {
+    pub fn new<'a>(results: impl IntoIterator<Item = &'a Randomization>) -> Self {
+        let mut stats = Self::default();
+        let mut scenarios = BTreeMap::<_, usize>::new();
+        let mut scenes = BTreeMap::<_, usize>::new();
+        for result in results {
+            stats.results += 1;
+            *scenarios.entry(result.scenario.name.clone()).or_default() += 1;
+            *scenes
+                .entry((result.scenario.name.clone(), result.scene.clone()))
+                .or_default() += 1;
+            for group in &result.messages {
+                stats.names.extend(group.names.iter().cloned());
+                if let [name] = group.names.as_slice() {
+                    *stats.solo.entry(name.clone()).or_default() += 1;
+                }
+                for (a, b) in group.names.iter().sorted().tuple_combinations() {
+                    *stats.pairs.entry((a.clone(), b.clone())).or_default() += 1;
+                }
+            }
+        }
+        stats.names = stats.names.into_iter().sorted().dedup().collect();
+        stats.scenarios = most_used(scenarios);
+        stats.scenes = most_used(scenes);
+        stats
+    }
+
+    /// How often `a` and `b` were in the same group.
+    pub fn pair_count(&self, a: &str, b: &str) -> usize {
+        let key = if a <= b { (a, b) } else { (b, a) };
+        self.pairs
+            .get(&(key.0.to_string(), key.1.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// All pairs of names that were never in the same group.
+    pub fn never_paired(&self) -> Vec<(&str, &str)> {
+        self.names
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| self.pair_count(a, b) == 0)
+            .map(|(a, b)| (a.as_str(), b.as_str()))
+            .collect()
+    }
+}
+
+/// The counted items, most used first and alphabetically among equals.
+fn most_used<K: Ord>(counts: BTreeMap<K, usize>) -> Vec<(K, usize)> {
+    counts
+        .into_iter()
+        .sorted_by(|(_, a), (_, b)| b.cmp(a))
+        .collect()
+}
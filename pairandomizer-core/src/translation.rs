@@ -0,0 +1,116 @@
+//! Translations of a scenario kept in the same file as the scenario itself,
+//! so all languages share the same scenes.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+use crate::{match_rank, Scenario, ScenarioMeta, Scene};
+
+/// A scene's name and messages in another language.
+///
+/// Anything left out falls back to the scene's own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SceneTranslation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triple_messages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solo_messages: Vec<String>,
+}
+
+/// The parts of a scenario outside of its scenes in another language.
+///
+/// Anything left out falls back to the scenario's own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioTranslation {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solo_messages: Vec<String>,
+}
+
+/// The translated list if there is one, the original otherwise.
+fn translated(translation: &[String], original: &[String]) -> Vec<String> {
+    if translation.is_empty() {
+        original.to_vec()
+    } else {
+        translation.to_vec()
+    }
+}
+
+impl Scene {
+    /// The scene in `lang`, as far as it is translated.
+    ///
+    /// The result has no translations of its own.
+    pub fn localized(&self, lang: &str) -> Scene {
+        let translation = self.translations.get(lang).cloned().unwrap_or_default();
+        Scene {
+            name: translation.name.unwrap_or_else(|| self.name.clone()),
+            messages: translated(&translation.messages, &self.messages),
+            triple_messages: translated(&translation.triple_messages, &self.triple_messages),
+            solo_messages: translated(&translation.solo_messages, &self.solo_messages),
+            translations: BTreeMap::new(),
+        }
+    }
+}
+
+impl Scenario {
+    /// The languages the scenario or any of its scenes is translated into.
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages = self
+            .translations
+            .keys()
+            .chain(
+                self.scenes
+                    .iter()
+                    .flat_map(|scene| scene.translations.keys()),
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+    /// The scenario in `lang`, as far as it is translated.
+    ///
+    /// The result has no translations of its own.
+    pub fn localized(&self, lang: &str) -> Scenario {
+        let translation = self.translations.get(lang).cloned().unwrap_or_default();
+        Scenario {
+            format_version: self.format_version,
+            scenes: self
+                .scenes
+                .iter()
+                .map(|scene| scene.localized(lang))
+                .collect(),
+            solo_messages: translated(&translation.solo_messages, &self.solo_messages),
+            translations: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScenarioMeta {
+    /// Adds the languages `scenario` is translated into to
+    /// [`ScenarioMeta::languages`], unless they are already listed.
+    pub fn add_languages_of(&mut self, scenario: &Scenario) {
+        for lang in scenario.languages() {
+            if self.lang.as_ref() != Some(&lang) && !self.languages.contains(&lang) {
+                self.languages.push(lang);
+            }
+        }
+    }
+    /// The language the scenario is offered in that comes first in `chain`,
+    /// along with its position there.
+    ///
+    /// Of equally good languages, the scenario's main one wins.
+    pub fn best_language(&self, chain: &[LanguageIdentifier]) -> Option<(usize, &str)> {
+        self.lang
+            .iter()
+            .chain(&self.languages)
+            .filter_map(|lang| Some((match_rank(chain, lang)?, lang.as_str())))
+            .min_by_key(|(rank, _)| *rank)
+    }
+}
@@ -0,0 +1,383 @@
+//! Checking index and scenario files for mistakes.
+
+use core::fmt;
+
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{placeholders, Index, Placeholder, Scenario, Scene, SceneTranslation};
+
+impl Index {
+    /// Checks the index for problems.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        if self.scenarios.is_empty() {
+            issues.push(Issue::new(Severity::Error, IssueKind::NoScenarios));
+        }
+        let mut filenames = HashSet::new();
+        for meta in self.scenarios.iter().chain(&self.beta) {
+            if !filenames.insert(&meta.filename) {
+                issues.push(Issue::new(
+                    Severity::Warning,
+                    IssueKind::DuplicateFilename(meta.filename.clone()),
+                ));
+            }
+        }
+        issues
+    }
+}
+
+impl Scenario {
+    /// Checks the scenario for problems.
+    ///
+    /// Errors make parts of the scenario unusable, warnings point out
+    /// messages that probably won't come out as intended.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        if self.scenes.is_empty() {
+            issues.push(Issue::new(Severity::Error, IssueKind::NoScenes));
+        }
+        let mut scene_names = HashSet::new();
+        for (scene_ix, scene) in self.scenes.iter().enumerate() {
+            let in_scene = |severity, kind| Issue {
+                scene: Some((scene_ix, scene.name.clone())),
+                ..Issue::new(severity, kind)
+            };
+            if scene.name.trim().is_empty() {
+                issues.push(in_scene(Severity::Warning, IssueKind::UnnamedScene));
+            } else if !scene_names.insert(&scene.name) {
+                issues.push(in_scene(Severity::Warning, IssueKind::DuplicateSceneName));
+            }
+            if scene.messages.is_empty() {
+                issues.push(in_scene(Severity::Error, IssueKind::NoMessages));
+            }
+            for kind in TemplateKind::ALL {
+                issues.extend(messages_issues(kind, kind.templates(scene), |ix, issue| {
+                    Issue {
+                        message: Some((kind, ix)),
+                        ..in_scene(Severity::Warning, issue)
+                    }
+                }));
+            }
+            for (lang, translation) in &scene.translations {
+                for kind in TemplateKind::ALL {
+                    issues.extend(messages_issues(
+                        kind,
+                        kind.translated(translation),
+                        |ix, issue| Issue {
+                            lang: Some(lang.clone()),
+                            message: Some((kind, ix)),
+                            ..in_scene(Severity::Warning, issue)
+                        },
+                    ));
+                }
+            }
+        }
+        issues.extend(messages_issues(
+            TemplateKind::Solo,
+            &self.solo_messages,
+            |ix, issue| Issue {
+                message: Some((TemplateKind::Solo, ix)),
+                ..Issue::new(Severity::Warning, issue)
+            },
+        ));
+        for (lang, translation) in &self.translations {
+            issues.extend(messages_issues(
+                TemplateKind::Solo,
+                &translation.solo_messages,
+                |ix, issue| Issue {
+                    lang: Some(lang.clone()),
+                    message: Some((TemplateKind::Solo, ix)),
+                    ..Issue::new(Severity::Warning, issue)
+                },
+            ));
+        }
+        issues
+    }
+}
+
+/// Checks a list of message templates, turning the problems found in the
+/// message at a position into issues with `at_message`.
+fn messages_issues(
+    kind: TemplateKind,
+    templates: &[String],
+    at_message: impl Fn(usize, IssueKind) -> Issue,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut messages = HashSet::new();
+    for (ix, message) in templates.iter().enumerate() {
+        if !messages.insert(message) {
+            issues.push(at_message(ix, IssueKind::DuplicateMessage));
+        }
+        issues.extend(
+            template_issues(message, kind.group_size())
+                .into_iter()
+                .map(|issue| at_message(ix, issue)),
+        );
+    }
+    issues
+}
+
+/// Checks a message template meant for groups of `group_size` names.
+fn template_issues(template: &str, group_size: usize) -> Vec<IssueKind> {
+    let placeholders = placeholders(template);
+    let mut issues = (1..=group_size)
+        .filter(|n| !placeholders.contains(&Placeholder::Name(*n)))
+        .map(IssueKind::MissingPlaceholder)
+        .collect_vec();
+    for placeholder in placeholders {
+        match placeholder {
+            Placeholder::Name(n) if n <= group_size => {}
+            Placeholder::Name(n) => {
+                issues.push(IssueKind::UnknownPlaceholder(format!("%{n}$s")));
+            }
+            Placeholder::Invalid(text) => {
+                issues.push(IssueKind::UnknownPlaceholder(text.to_string()));
+            }
+        }
+    }
+    issues
+}
+
+/// How bad a problem found by validation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Probably a mistake, but doesn't break anything.
+    Warning,
+    /// The affected part can't be used.
+    Error,
+}
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem in an index or scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+    /// Index and name of the scene the issue is in.
+    pub scene: Option<(usize, String)>,
+    /// Which list of the scene the message the issue is about is in, and its
+    /// index there.
+    pub message: Option<(TemplateKind, usize)>,
+    /// The language of the translation the message is in, if it isn't in
+    /// the scenario's main language.
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+impl Issue {
+    fn new(severity: Severity, kind: IssueKind) -> Self {
+        Self {
+            severity,
+            kind,
+            scene: None,
+            message: None,
+            lang: None,
+        }
+    }
+}
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scene = match &self.scene {
+            Some((_, name)) if !name.trim().is_empty() => Some(format!("scene \"{name}\"")),
+            Some((ix, _)) => Some(format!("scene {}", ix + 1)),
+            None => None,
+        };
+        let lang = self
+            .lang
+            .as_ref()
+            .map(|lang| format!("translation \"{lang}\""));
+        let message = self.message.map(|(kind, ix)| format!("{kind} {}", ix + 1));
+        let location = scene.into_iter().chain(lang).chain(message).join(", ");
+        if location.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{location}: {}", self.kind)
+        }
+    }
+}
+
+/// The message lists of a scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateKind {
+    /// [`Scene::messages`]
+    Pair,
+    /// [`Scene::triple_messages`]
+    Triple,
+    /// [`Scene::solo_messages`], or [`crate::Scenario::solo_messages`] for
+    /// issues outside of a scene.
+    Solo,
+}
+impl TemplateKind {
+    /// All kinds, in the order they appear in a scene.
+    pub const ALL: [Self; 3] = [Self::Pair, Self::Triple, Self::Solo];
+
+    /// The messages of this kind in `scene`.
+    pub fn templates(self, scene: &Scene) -> &[String] {
+        match self {
+            Self::Pair => &scene.messages,
+            Self::Triple => &scene.triple_messages,
+            Self::Solo => &scene.solo_messages,
+        }
+    }
+    /// The messages of this kind in a scene's translation.
+    pub fn translated(self, translation: &SceneTranslation) -> &[String] {
+        match self {
+            Self::Pair => &translation.messages,
+            Self::Triple => &translation.triple_messages,
+            Self::Solo => &translation.solo_messages,
+        }
+    }
+    /// How many names the messages of this kind are written for.
+    pub fn group_size(self) -> usize {
+        match self {
+            Self::Pair => 2,
+            Self::Triple => 3,
+            Self::Solo => 1,
+        }
+    }
+}
+impl fmt::Display for TemplateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pair => write!(f, "message"),
+            Self::Triple => write!(f, "triple message"),
+            Self::Solo => write!(f, "solo message"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    NoScenarios,
+    DuplicateFilename(String),
+    NoScenes,
+    UnnamedScene,
+    DuplicateSceneName,
+    NoMessages,
+    DuplicateMessage,
+    MissingPlaceholder(usize),
+    UnknownPlaceholder(String),
+}
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoScenarios => write!(f, "no scenarios"),
+            Self::DuplicateFilename(filename) => write!(f, "{filename} is listed more than once"),
+            Self::NoScenes => write!(f, "no scenes"),
+            Self::UnnamedScene => write!(f, "no name"),
+            Self::DuplicateSceneName => write!(f, "duplicate scene name"),
+            Self::NoMessages => write!(f, "no messages"),
+            Self::DuplicateMessage => write!(f, "duplicate message"),
+            Self::MissingPlaceholder(n) => write!(f, "missing %{n}$s"),
+            Self::UnknownPlaceholder(text) => write!(f, "unknown placeholder {text}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_placeholders() {
+        assert_eq!(
+            placeholders("%2$s meets %1$s%3$s, 100% sure"),
+            [
+                Placeholder::Name(2),
+                Placeholder::Name(1),
+                Placeholder::Name(3)
+            ]
+        );
+        assert_eq!(
+            placeholders("%0$s %$s %1s %12$x done"),
+            [
+                Placeholder::Invalid("%0$s"),
+                Placeholder::Invalid("%$s"),
+                Placeholder::Invalid("%1s"),
+                Placeholder::Invalid("%12$x"),
+            ]
+        );
+        assert_eq!(placeholders("no placeholders, 50 %"), []);
+    }
+
+    #[test]
+    fn template_issues_for_group_size() {
+        assert_eq!(template_issues("%1$s and %2$s", 2), []);
+        assert_eq!(
+            template_issues("%2$s waits", 2),
+            [IssueKind::MissingPlaceholder(1)]
+        );
+        assert_eq!(
+            template_issues("%1$s, %2$s and %3$s", 2),
+            [IssueKind::UnknownPlaceholder("%3$s".to_string())]
+        );
+        assert_eq!(
+            template_issues("%1$s and %2s", 2),
+            [
+                IssueKind::MissingPlaceholder(2),
+                IssueKind::UnknownPlaceholder("%2s".to_string()),
+            ]
+        );
+        assert_eq!(template_issues("%1$s alone", 1), []);
+    }
+
+    #[test]
+    fn scenario_issues_point_at_messages() {
+        let scenario = Scenario {
+            scenes: vec![
+                Scene {
+                    name: "Beach".to_string(),
+                    messages: vec!["%1$s and %2$s".to_string(); 2],
+                    triple_messages: vec!["%1$s and %2$s".to_string()],
+                    ..Scene::default()
+                },
+                Scene {
+                    name: "Beach".to_string(),
+                    ..Scene::default()
+                },
+            ],
+            ..Scenario::default()
+        };
+        let issues = scenario.validate();
+        let beach = Some((0, "Beach".to_string()));
+        assert_eq!(
+            issues,
+            [
+                Issue {
+                    message: Some((TemplateKind::Pair, 1)),
+                    scene: beach.clone(),
+                    ..Issue::new(Severity::Warning, IssueKind::DuplicateMessage)
+                },
+                Issue {
+                    message: Some((TemplateKind::Triple, 0)),
+                    scene: beach,
+                    ..Issue::new(Severity::Warning, IssueKind::MissingPlaceholder(3))
+                },
+                Issue {
+                    scene: Some((1, "Beach".to_string())),
+                    ..Issue::new(Severity::Warning, IssueKind::DuplicateSceneName)
+                },
+                Issue {
+                    scene: Some((1, "Beach".to_string())),
+                    ..Issue::new(Severity::Error, IssueKind::NoMessages)
+                },
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "scene \"Beach\", triple message 1: missing %3$s"
+        );
+    }
+}
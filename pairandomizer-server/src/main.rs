@@ -0,0 +1,288 @@
+//! Serves a directory of scenario files to the app, generating the index
+//! from the files that are there and picking up changes while running.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, RwLock},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use notify::{Event, RecursiveMode, Watcher};
+use pairandomizer_core::{
+    parse_versioned, FormatVersion, Index, Scenario, ScenarioMeta, ScenarioSource, Severity,
+    INDEX_FILENAME,
+};
+use sha2::{Digest, Sha256};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// The path the app expects the index and scenario files under.
+const PREFIX: &str = "/res/json/";
+/// How long to wait for more changes before reloading, as editors tend to
+/// touch files several times when saving.
+const RELOAD_DELAY: Duration = Duration::from_millis(200);
+
+/// Serves a directory of scenario files for use as a custom server.
+///
+/// The index is generated from the scenario files in the directory. If there
+/// is an index.json, it is used for the server name and scenario names.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// The directory containing the scenario files.
+    #[arg(default_value = ".")]
+    dir: PathBuf,
+    /// The address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+    /// The server name shown in the app; defaults to the one in index.json
+    /// or the directory name.
+    #[arg(short, long)]
+    name: Option<String>,
+}
+
+/// A file ready to be served.
+struct File {
+    body: Vec<u8>,
+    etag: String,
+}
+impl File {
+    fn new(body: Vec<u8>) -> Self {
+        let etag = format!("\"{}\"", hash(&body));
+        Self { body, etag }
+    }
+}
+
+/// Everything that is served, keyed by filename.
+type Files = HashMap<String, File>;
+
+fn hash(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Reads all scenario files in `dir` and generates an index for them.
+///
+/// Files that can't be read or parsed are left out with a message on stderr.
+fn scan(dir: &Path, name: Option<&str>) -> Result<Files> {
+    let index_path = dir.join(INDEX_FILENAME);
+    let mut index = if index_path.exists() {
+        let text =
+            fs::read_to_string(&index_path).with_context(|| format!("{}", index_path.display()))?;
+        parse_versioned::<Index>(&text).with_context(|| format!("{}", index_path.display()))?
+    } else {
+        Index {
+            format_version: FormatVersion::CURRENT,
+            server_name: dir
+                .canonicalize()?
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            comment: String::new(),
+            scenarios: Vec::new(),
+            beta: Vec::new(),
+        }
+    };
+    if let Some(name) = name {
+        index.server_name = name.to_string();
+    }
+
+    let mut filenames = fs::read_dir(dir)
+        .with_context(|| format!("{}", dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|filename| filename.ends_with(".json") && filename != INDEX_FILENAME)
+        .collect::<Vec<_>>();
+    filenames.sort();
+
+    let mut files = Files::new();
+    for filename in filenames {
+        let path = dir.join(&filename);
+        let body = match fs::read(&path) {
+            Ok(body) => body,
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                continue;
+            }
+        };
+        let scenario = match std::str::from_utf8(&body)
+            .map_err(anyhow::Error::from)
+            .and_then(parse_versioned::<Scenario>)
+        {
+            Ok(scenario) => scenario,
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                continue;
+            }
+        };
+        for issue in scenario.validate() {
+            if issue.severity == Severity::Error {
+                eprintln!("{}: {issue}", path.display());
+            }
+        }
+        let file = File::new(body);
+        let listed = index
+            .scenarios
+            .iter_mut()
+            .chain(&mut index.beta)
+            .find(|meta| meta.filename == filename);
+        let meta = match listed {
+            Some(meta) => meta,
+            None => {
+                let meta = ScenarioMeta::from_filename(&filename, ScenarioSource::Server);
+                index.scenarios.push(meta);
+                index.scenarios.last_mut().unwrap()
+            }
+        };
+        meta.add_languages_of(&scenario);
+        meta.hash = Some(file.etag.trim_matches('"').to_string());
+        files.insert(filename, file);
+    }
+
+    for meta in index.scenarios.iter().chain(&index.beta) {
+        if !files.contains_key(&meta.filename) {
+            eprintln!(
+                "{}: {} is listed but can't be served",
+                index_path.display(),
+                meta.filename
+            );
+        }
+    }
+    index
+        .scenarios
+        .retain(|meta| files.contains_key(&meta.filename));
+    index.beta.retain(|meta| files.contains_key(&meta.filename));
+    files.insert(
+        INDEX_FILENAME.to_string(),
+        File::new(serde_json::to_vec_pretty(&index)?),
+    );
+    Ok(files)
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("header names and values are ASCII")
+}
+
+/// Headers allowing the app to fetch from another origin and see the ETag.
+fn cors_headers() -> [Header; 4] {
+    [
+        header("Access-Control-Allow-Origin", "*"),
+        header("Access-Control-Allow-Methods", "GET, HEAD, OPTIONS"),
+        header("Access-Control-Allow-Headers", "If-None-Match"),
+        header("Access-Control-Expose-Headers", "ETag"),
+    ]
+}
+
+/// A response with the CORS headers set.
+fn response(status: u16, body: Vec<u8>) -> Response<Cursor<Vec<u8>>> {
+    let mut response = Response::from_data(body).with_status_code(status);
+    for header in cors_headers() {
+        response.add_header(header);
+    }
+    response
+}
+
+/// Decodes the `%XX` escapes browsers use for spaces and non-ASCII
+/// characters in a URL path.
+///
+/// Returns None for invalid escapes or text.
+fn decode_path(path: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte == b'%' {
+            let hex = rest
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &rest[2..];
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn respond(request: Request, files: &RwLock<Files>) -> io::Result<()> {
+    match request.method() {
+        Method::Options => return request.respond(response(204, Vec::new())),
+        Method::Get | Method::Head => {}
+        _ => return request.respond(response(405, Vec::new())),
+    }
+    let path = request.url().split('?').next().unwrap_or_default();
+    let files = files.read().unwrap();
+    let Some(file) = path
+        .strip_prefix(PREFIX)
+        .and_then(decode_path)
+        .and_then(|filename| files.get(&filename))
+    else {
+        return request.respond(response(404, Vec::new()));
+    };
+    let not_modified = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("If-None-Match"))
+        .is_some_and(|h| {
+            h.value
+                .as_str()
+                .split(',')
+                .any(|tag| tag.trim() == file.etag)
+        });
+    let mut response = if not_modified {
+        response(304, Vec::new())
+    } else {
+        response(200, file.body.clone())
+            .with_header(header("Content-Type", "application/json; charset=utf-8"))
+    };
+    response.add_header(header("ETag", &file.etag));
+    response.add_header(header("Cache-Control", "no-cache"));
+    request.respond(response)
+}
+
+/// Rescans the directory whenever something in it changes.
+fn watch(dir: PathBuf, name: Option<String>, files: Arc<RwLock<Files>>) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        // Scanning reads the files, which must not trigger another scan
+        if !event.is_ok_and(|event| event.kind.is_access()) {
+            let _ = sender.send(());
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    thread::spawn(move || {
+        // Keep the watcher alive as long as the thread runs
+        let _watcher = watcher;
+        while receiver.recv().is_ok() {
+            while receiver.recv_timeout(RELOAD_DELAY).is_ok() {}
+            match scan(&dir, name.as_deref()) {
+                Ok(scanned) => {
+                    *files.write().unwrap() = scanned;
+                    eprintln!("reloaded {}", dir.display());
+                }
+                Err(error) => eprintln!("reload failed, keeping the old files: {error:#}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let files = Arc::new(RwLock::new(scan(&args.dir, args.name.as_deref())?));
+    watch(args.dir.clone(), args.name.clone(), files.clone())?;
+    let server = Server::http(&args.listen).map_err(|error| anyhow!("{}: {error}", args.listen))?;
+    eprintln!("serving {} on http://{}", args.dir.display(), args.listen);
+    for request in server.incoming_requests() {
+        if let Err(error) = respond(request, &files) {
+            eprintln!("failed to respond: {error}");
+        }
+    }
+    Ok(())
+}
@@ -1,32 +1,122 @@
-use std::rc::Rc;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
-use bounce::prelude::*;
-use gloo::storage::{LocalStorage, Storage};
+use bounce::{prelude::*, UseAtomHandle};
+use fluent::fluent_args;
+use gloo::{
+    storage::{LocalStorage, Storage},
+    timers::future::TimeoutFuture,
+};
 use itertools::Itertools;
-use rand::prelude::SliceRandom;
+use pairandomizer_core::{
+    parse_names, Issue, IssueKind, LoadedIndex, RandomizeError, ScenarioMeta, ScenarioSource,
+    Severity, TemplateKind, Unavailable, UnsupportedVersion, DEFAULT_GROUP_SIZE,
+};
+use serde::{Deserialize, Serialize};
 use unic_langid::LanguageIdentifier;
-use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
 use crate::{
+    history::{History, HistoryEntry},
+    i18n::{use_translations, Translations},
+    language::Languages,
+    loader::{self, FetchError},
+    local_scenarios::LocalScenarios,
     modal::{Dialog, ModalSender},
-    pairandomizer_core::{Index, LoadedIndex},
     settings::Settings,
 };
 
 mod navbar;
+mod result;
+
+pub use result::ResultDialog;
 
 #[cfg(debug_assertions)]
 const DEFAULT_SERVER: &str = "http://127.0.0.1:8080";
 #[cfg(not(debug_assertions))]
 const DEFAULT_SERVER: &str = "https://pr.karp.lol";
 
-#[derive(PartialEq, Default, Atom)]
-pub struct CurrentIndex(Option<LoadedIndex>);
+/// How often a failed index load is retried automatically.
+const MAX_RETRIES: u32 = 5;
+/// Delay before the first automatic retry; doubled on every further attempt.
+const RETRY_BASE_DELAY_MS: u32 = 1000;
+
+const INDEX_STORAGE_KEY: &str = "index";
+
+/// The index in use, persisted so the app can start with the last known data
+/// before (or without) reaching the server.
+#[derive(PartialEq, Atom, Serialize, Deserialize)]
+#[bounce(observed)]
+pub struct CurrentIndex {
+    index: Option<LoadedIndex>,
+    /// When the index was last loaded successfully, in milliseconds since the
+    /// unix epoch.
+    pub updated_at: Option<f64>,
+    /// Whether the index was loaded from the server in this session, as
+    /// opposed to being restored from storage.
+    #[serde(skip)]
+    pub live: bool,
+}
+impl CurrentIndex {
+    fn loaded(index: LoadedIndex) -> Self {
+        Self {
+            index: Some(index),
+            updated_at: Some(js_sys::Date::now()),
+            live: true,
+        }
+    }
+}
+impl Default for CurrentIndex {
+    fn default() -> Self {
+        LocalStorage::get(INDEX_STORAGE_KEY).unwrap_or(Self {
+            index: None,
+            updated_at: None,
+            live: false,
+        })
+    }
+}
+impl Observed for CurrentIndex {
+    fn changed(self: Rc<Self>) {
+        // Only freshly loaded data is worth persisting, the rest is already
+        // in storage.
+        if self.live {
+            if let Err(error) = LocalStorage::set(INDEX_STORAGE_KEY, &*self) {
+                gloo::console::error!(format!("Failed to save index: {:?}", error));
+            }
+        }
+    }
+}
 impl std::ops::Deref for CurrentIndex {
     type Target = Option<LoadedIndex>;
 
+    fn deref(&self) -> &Self::Target {
+        &self.index
+    }
+}
+
+/// The server index combined with the local scenarios; what scenarios are
+/// actually picked from.
+#[derive(PartialEq)]
+pub struct AvailableIndex(Option<LoadedIndex>);
+impl Selector for AvailableIndex {
+    fn select(states: &BounceStates) -> Rc<Self> {
+        let current = states.get_atom_value::<CurrentIndex>();
+        let local = states.get_atom_value::<LocalScenarios>();
+        let index = match (&**current, local.0.is_empty()) {
+            (index, true) => index.clone(),
+            (index, false) => {
+                let mut index = index.clone().unwrap_or_else(LoadedIndex::local_only);
+                index.scenarios.extend(local.0.iter().cloned());
+                Some(index)
+            }
+        };
+        Rc::new(Self(index))
+    }
+}
+impl std::ops::Deref for AvailableIndex {
+    type Target = Option<LoadedIndex>;
+
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -34,7 +124,7 @@ impl std::ops::Deref for CurrentIndex {
 
 #[derive(PartialEq, Atom)]
 #[bounce(observed)]
-pub struct Names(Vec<String>);
+pub struct Names(pub Vec<String>);
 impl Default for Names {
     fn default() -> Self {
         Self(LocalStorage::get("names").unwrap_or_default())
@@ -46,123 +136,477 @@ impl Observed for Names {
     }
 }
 
+/// Why loading the index failed for good, once there are no more retries.
+#[derive(Default, Atom)]
+pub struct IndexLoadError(pub Option<Arc<anyhow::Error>>);
+impl PartialEq for IndexLoadError {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum State {
     Initial,
     Loading,
     Loaded,
-    Error(std::sync::Arc<anyhow::Error>),
+    Error {
+        error: Arc<anyhow::Error>,
+        /// Milliseconds until the next automatic retry, if there is one.
+        retry_in: Option<u32>,
+    },
 }
 impl State {
     pub fn is_initial(&self) -> bool {
         matches!(self, Self::Initial)
     }
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Initial | Self::Loading)
+    }
+}
+
+/// Loads the index from the default server, retrying with exponential backoff
+/// on failure.
+///
+/// `generation` is bumped every time a new load is started; an older load
+/// notices that and stops instead of overwriting the newer one's results.
+async fn load_index(
+    index: UseAtomHandle<CurrentIndex>,
+    load_error: UseAtomHandle<IndexLoadError>,
+    state: UseStateSetter<State>,
+    generation: Rc<RefCell<u32>>,
+) {
+    let own_generation = *generation.borrow();
+    let is_current = || *generation.borrow() == own_generation;
+    let previous = Option::clone(&index);
+    let mut attempt = 0;
+    load_error.set(IndexLoadError(None));
+    loop {
+        state.set(State::Loading);
+        let result = loader::load(DEFAULT_SERVER, previous.as_ref()).await;
+        if !is_current() {
+            return;
+        }
+        match result {
+            Ok(ix) => {
+                index.set(CurrentIndex::loaded(ix));
+                state.set(State::Loaded);
+                return;
+            }
+            Err(err) => {
+                let retry_in = (attempt < MAX_RETRIES).then(|| RETRY_BASE_DELAY_MS << attempt);
+                let error = Arc::new(err);
+                state.set(State::Error {
+                    error: error.clone(),
+                    retry_in,
+                });
+                match retry_in {
+                    Some(delay) => TimeoutFuture::new(delay).await,
+                    None => {
+                        load_error.set(IndexLoadError(Some(error)));
+                        return;
+                    }
+                }
+                if !is_current() {
+                    return;
+                }
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// The name of a scenario as shown to the user, marking beta scenarios.
+pub fn scenario_title(tr: &Translations, meta: &ScenarioMeta) -> String {
+    let args = fluent_args!["name" => meta.name.clone()];
+    match (meta.beta, meta.source) {
+        (true, _) => tr.format("scenario-beta", Some(&args)),
+        (false, ScenarioSource::Local) => tr.format("scenario-local", Some(&args)),
+        (false, ScenarioSource::Server) => meta.name.clone(),
+    }
+}
+
+/// Explains to the user why randomizing failed.
+pub fn randomize_error_text(tr: &Translations, error: &RandomizeError) -> String {
+    match error {
+        RandomizeError::NoScenarios => tr.get("error-no-scenarios"),
+        RandomizeError::NoScenes(scenario) => tr.format(
+            "error-no-scenes",
+            Some(&fluent_args!["scenario" => scenario.clone()]),
+        ),
+        RandomizeError::NoMessages(scene) => tr.format(
+            "error-no-messages",
+            Some(&fluent_args!["scene" => scene.clone()]),
+        ),
+        RandomizeError::NotEnoughNames(count) => tr.format(
+            "error-not-enough-names",
+            Some(&fluent_args!["count" => *count]),
+        ),
+    }
+}
+
+/// The name of a severity as shown to the user.
+pub fn severity_text(tr: &Translations, severity: Severity) -> String {
+    match severity {
+        Severity::Warning => tr.get("severity-warning"),
+        Severity::Error => tr.get("severity-error"),
+    }
+}
+
+/// Describes a problem found in a file, along with where it is.
+pub fn issue_text(tr: &Translations, issue: &Issue) -> String {
+    let kind = match &issue.kind {
+        IssueKind::NoScenarios => tr.get("issue-no-scenarios"),
+        IssueKind::DuplicateFilename(filename) => tr.format(
+            "issue-duplicate-filename",
+            Some(&fluent_args!["filename" => filename.clone()]),
+        ),
+        IssueKind::NoScenes => tr.get("issue-no-scenes"),
+        IssueKind::UnnamedScene => tr.get("issue-unnamed-scene"),
+        IssueKind::DuplicateSceneName => tr.get("issue-duplicate-scene-name"),
+        IssueKind::NoMessages => tr.get("issue-no-messages"),
+        IssueKind::DuplicateMessage => tr.get("issue-duplicate-message"),
+        IssueKind::MissingPlaceholder(n) => tr.format(
+            "issue-missing-placeholder",
+            Some(&fluent_args!["placeholder" => format!("%{n}$s")]),
+        ),
+        IssueKind::UnknownPlaceholder(text) => tr.format(
+            "issue-unknown-placeholder",
+            Some(&fluent_args!["placeholder" => text.clone()]),
+        ),
+    };
+    let scene = issue.scene.as_ref().map(|(ix, name)| {
+        if name.trim().is_empty() {
+            tr.format(
+                "issue-scene-number",
+                Some(&fluent_args!["number" => ix + 1]),
+            )
+        } else {
+            tr.format("issue-scene", Some(&fluent_args!["name" => name.clone()]))
+        }
+    });
+    let lang = issue.lang.as_ref().map(|lang| {
+        tr.format(
+            "issue-translation",
+            Some(&fluent_args!["lang" => lang.clone()]),
+        )
+    });
+    let message = issue.message.map(|(kind, ix)| {
+        let id = match kind {
+            TemplateKind::Pair => "issue-message",
+            TemplateKind::Triple => "issue-triple-message",
+            TemplateKind::Solo => "issue-solo-message",
+        };
+        tr.format(id, Some(&fluent_args!["number" => ix + 1]))
+    });
+    let location = scene.into_iter().chain(lang).chain(message).join(", ");
+    if location.is_empty() {
+        kind
+    } else {
+        tr.format(
+            "issue-at",
+            Some(&fluent_args!["location" => location, "issue" => kind]),
+        )
+    }
+}
+
+/// Explains an error to the user, translating the ones the app knows about.
+///
+/// Others are shown as they are, along with their context.
+pub fn error_text(tr: &Translations, error: &anyhow::Error) -> String {
+    if let Some(unsupported) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<UnsupportedVersion>())
+    {
+        let args = fluent_args![
+            "found" => unsupported.found.to_string(),
+            "supported" => unsupported.supported.to_string(),
+        ];
+        return if unsupported.needs_update() {
+            tr.format("error-format-too-new", Some(&args))
+        } else {
+            tr.format("error-format-too-old", Some(&args))
+        };
+    }
+    if let Some(fetch_error) = error.downcast_ref::<FetchError>() {
+        return match fetch_error {
+            FetchError::Unreachable { url, error } => tr.format(
+                "error-unreachable",
+                Some(&fluent_args!["url" => url.clone(), "error" => error.clone()]),
+            ),
+            FetchError::Status { url, status, text } => tr.format(
+                "error-status",
+                Some(&fluent_args![
+                    "url" => url.clone(),
+                    "status" => *status,
+                    "text" => text.clone(),
+                ]),
+            ),
+        };
+    }
+    if let Some(unavailable) = error.downcast_ref::<Unavailable>() {
+        return match unavailable {
+            Unavailable::Scenario(filename) => tr.format(
+                "error-scenario-unavailable",
+                Some(&fluent_args!["filename" => filename.clone()]),
+            ),
+            Unavailable::Scene { filename, scene } => tr.format(
+                "error-scene-unavailable",
+                Some(&fluent_args!["filename" => filename.clone(), "scene" => scene.clone()]),
+            ),
+        };
+    }
+    if let Some(error) = error.downcast_ref::<RandomizeError>() {
+        return randomize_error_text(tr, error);
+    }
+    format!("{error:#}")
+}
+
+/// Formats a unix timestamp in milliseconds as a local date and time.
+pub fn format_timestamp(timestamp: f64, lang_id: &LanguageIdentifier) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp))
+        .to_locale_string(&lang_id.to_string(), &JsValue::UNDEFINED)
+        .into()
 }
 
 #[function_component(App)]
 pub fn app() -> Html {
     // Context
-    let lang_id = use_context::<LanguageIdentifier>().unwrap();
+    let languages = use_context::<Languages>().unwrap();
+    let tr = use_translations();
     let modal = use_context::<ModalSender>().unwrap();
     // Data
     let index = use_atom::<CurrentIndex>();
+    let load_error = use_atom::<IndexLoadError>();
+    let available = use_selector_value::<AvailableIndex>();
     let names = use_atom::<Names>();
+    let history = use_atom::<History>();
     // Settings
     let settings = use_atom_value::<Settings>();
 
     // State
     let loading_state = use_state(|| State::Initial);
-    if loading_state.is_initial() {
+    let generation = use_mut_ref(|| 0);
+    let reload = {
         let index = index.clone();
-        loading_state.set(State::Loading);
         let loading_state = loading_state.setter();
-        spawn_local(async move {
-            match Index::load(DEFAULT_SERVER).await {
-                Ok(ix) => {
-                    index.set(CurrentIndex(Some(ix)));
-                    loading_state.set(State::Loaded);
-                }
-                Err(err) => loading_state.set(State::Error(std::sync::Arc::new(err))),
-            }
-        });
+        Callback::from(move |_| {
+            *generation.borrow_mut() += 1;
+            spawn_local(load_index(
+                index.clone(),
+                load_error.clone(),
+                loading_state.clone(),
+                generation.clone(),
+            ));
+        })
+    };
+    if loading_state.is_initial() {
+        loading_state.set(State::Loading);
+        reload.emit(());
     }
 
-    let status_text = match &*loading_state {
-        State::Initial | State::Loading => "⏳ Loading…".to_string(),
-        State::Loaded => {
+    let cached_from = Option::as_ref(&index).filter(|_| !index.live).map(|index| {
+        tr.format(
+            "status-cached",
+            Some(&fluent_args!["server" => index.inner.server_name.clone()]),
+        )
+    });
+    let status_text = match (&*loading_state, cached_from) {
+        (State::Initial | State::Loading, None) => tr.get("status-loading"),
+        (State::Initial | State::Loading, Some(cached_from)) => tr.format(
+            "status-refreshing",
+            Some(&fluent_args!["cached" => cached_from]),
+        ),
+        (State::Loaded, _) => {
             if let Some(index) = &**index {
-                format!("✅ Loaded from server: {}", index.inner.server_name)
+                tr.format(
+                    "status-loaded",
+                    Some(&fluent_args!["server" => index.inner.server_name.clone()]),
+                )
             } else {
-                "✅ Loaded, waiting for data...".to_string()
+                tr.get("status-loaded-waiting")
+            }
+        }
+        (State::Error { error, retry_in }, cached_from) => {
+            let mut text = tr.format(
+                "status-error",
+                Some(&fluent_args!["error" => error_text(&tr, error)]),
+            );
+            if let Some(cached_from) = cached_from {
+                text += &format!(", {cached_from}");
+            }
+            if let Some(delay) = retry_in {
+                let retrying = tr.format(
+                    "status-retrying",
+                    Some(&fluent_args!["seconds" => delay / 1000]),
+                );
+                text += &format!(" ({retrying})");
             }
+            text
         }
-        State::Error(error) => format!("❌ Error: {}", error),
     };
+    let last_updated = index.updated_at.map(|timestamp| {
+        tr.format(
+            "last-updated",
+            Some(&fluent_args!["time" => format_timestamp(timestamp, languages.primary())]),
+        )
+    });
+    let issues = Option::as_ref(&index)
+        .filter(|index| !index.issues.is_empty())
+        .map(|index| {
+            let issues = index.issues.clone();
+            let count = issues.len();
+            let tooltip = tr.format("issues-tooltip", Some(&fluent_args!["count" => count]));
+            let modal = modal.clone();
+            let tr = tr.clone();
+            let onclick = Callback::from(move |_| {
+                let issues = issues.iter().map(|(filename, issue)| {
+                    let icon = match issue.severity {
+                        Severity::Warning => "⚠️",
+                        Severity::Error => "❌",
+                    };
+                    html! {
+                      <li title={severity_text(&tr, issue.severity)}>
+                        { format!("{icon} {filename}, {}", issue_text(&tr, issue)) }
+                      </li>
+                    }
+                });
+                modal.open(html! {
+                  <Dialog title={tr.get("issues-title")}>
+                    <ul class="issues">{ for issues }</ul>
+                    <div class="buttons">
+                      <span />
+                      <button onclick={modal.close_callback()}>{ tr.get("close") }</button>
+                    </div>
+                  </Dialog>
+                });
+            });
+            html! {
+              <button {onclick} title={tooltip}>
+                { format!("⚠️ {count}") }
+              </button>
+            }
+        });
+    let retry = matches!(*loading_state, State::Error { .. }).then(|| {
+        let reload = reload.clone();
+        html! {
+          <button onclick={move |_| reload.emit(())}>{ tr.get("retry") }</button>
+        }
+    });
 
     let update_names = {
         let names = names.clone();
         Callback::from(move |e: InputEvent| {
             let ta = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>();
-            let new_names = ta
-                .value()
-                .trim()
-                .split('\n')
-                .map(str::trim)
-                .filter(|n| !n.is_empty())
-                .unique()
-                .map(str::to_string)
-                .collect();
-            names.set(Names(new_names))
+            names.set(Names(parse_names(&ta.value())))
         })
     };
 
-    let generate = index
-        .is_some()
-        .then(|| {
-            let index = index.clone();
-            let names = names.clone();
-            Callback::from(move |_| {
-                let index = match &**index {
-                    Some(index) => index,
-                    None => return,
-                };
-                let mut rng = rand::thread_rng();
-                let (meta, scenario) = index.pick_scenario(&mut rng, &settings, &lang_id);
-                let scene = settings
-                    .scene_index
-                    .and_then(|index| scenario.scenes.get(index))
-                    .unwrap_or_else(|| scenario.scenes.choose(&mut rng).unwrap());
-                let messages = scene.randomize(&names.0);
-                let title = meta.name.clone() + " - " + &scene.name;
-                let msgs = messages.into_iter().map(|msg| {
-                    html! { <>
-                      { msg.to_string() }
-                      <br/>
-                    </> }
-                });
-                modal.open(html! {
-                  <Dialog {title}>
-                    <div>{ for msgs }</div>
-                    <div class="buttons">
-                      <span />
-                      <button onclick={modal.close_callback()}>{"Close"}</button>
-                    </div>
-                  </Dialog>
+    let generate = {
+        let index = available.clone();
+        let languages = languages.clone();
+        let settings = settings.clone();
+        let names = names.clone();
+        let history = history.clone();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            let index = match &**index {
+                Some(index) => index,
+                None => return,
+            };
+            // Only the randomizing is seeded, so a link needs just the scene
+            // and not how it was picked
+            let seed = rand::random();
+            let result = index
+                .pick_scene(
+                    &mut rand::thread_rng(),
+                    &settings.pick_options(),
+                    languages.chain(),
+                    names.0.len(),
+                )
+                .and_then(|picked| {
+                    let result = picked.randomize_seeded(seed, &names.0, DEFAULT_GROUP_SIZE)?;
+                    Ok((picked, result))
                 });
-            })
+            match result {
+                Ok((picked, result)) => {
+                    let entry = HistoryEntry::new(
+                        js_sys::Date::now(),
+                        seed,
+                        DEFAULT_GROUP_SIZE,
+                        names.0.clone(),
+                        result,
+                    );
+                    history.set(history.with(entry.clone()));
+                    modal.open(html! {
+                      <ResultDialog
+                        picked={Rc::new(picked)}
+                        {entry}
+                        onclose={modal.close_callback()}
+                        />
+                    });
+                }
+                Err(error) => {
+                    let error = randomize_error_text(&tr, &error);
+                    modal.open(html! {
+                      <Dialog title={tr.get("cant-randomize")}>
+                        <div>{ tr.format("status-error", Some(&fluent_args!["error" => error])) }</div>
+                        <div class="buttons">
+                          <span />
+                          <button onclick={modal.close_callback()}>{ tr.get("close") }</button>
+                        </div>
+                      </Dialog>
+                    });
+                }
+            }
         })
-        .unwrap_or_default();
+    };
+
+    // Why the randomize button is disabled, shown as its tooltip
+    let cant_randomize = match &**available {
+        None => Some(tr.get("waiting-for-scenarios")),
+        Some(index) => {
+            let min_names = index.min_names_for(&settings.pick_options(), languages.chain());
+            (names.0.len() < min_names).then(|| {
+                tr.format(
+                    "not-enough-names",
+                    Some(&fluent_args!["count" => min_names]),
+                )
+            })
+        }
+    };
 
     html! {
       <div class="layout-container">
-        <navbar::Navbar />
+        <navbar::Navbar
+          onrefresh={reload}
+          refreshing={loading_state.is_loading()}
+          />
         <div class="input-container">
-          <textarea oninput={update_names} value={names.0.clone().join("\n")} />
+          <textarea
+            placeholder={tr.get("names-placeholder")}
+            oninput={update_names}
+            value={names.0.clone().join("\n")}
+            />
         </div>
         <div class="buttons">
-          <span>{ status_text }</span>
-          <button disabled={index.is_none()} onclick={generate}>
-            { format!("Randomize! ({} names)", names.0.len()) }
+          <div class="status">
+            <span>{ status_text }</span>
+            if let Some(last_updated) = last_updated {
+              <small>{ last_updated }</small>
+            }
+          </div>
+          { issues.unwrap_or_default() }
+          { retry.unwrap_or_default() }
+          <button
+            disabled={cant_randomize.is_some()}
+            title={cant_randomize.clone()}
+            onclick={generate}
+            >
+            { tr.format("randomize", Some(&fluent_args!["count" => names.0.len()])) }
           </button>
         </div>
       </div>
@@ -1,22 +1,64 @@
 use yew::prelude::*;
 
-use crate::{modal::ModalSender, settings::SettingsDialog};
+use crate::{
+    history::HistoryDialog, i18n::use_translations, modal::ModalSender, roster::ShareRosterDialog,
+    settings::SettingsDialog,
+};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct NavbarProps {
+    /// Re-runs the index load.
+    pub onrefresh: Callback<()>,
+    /// Whether the index is currently being loaded.
+    pub refreshing: bool,
+}
 
 #[function_component(Navbar)]
-pub fn navbar() -> Html {
+pub fn navbar(props: &NavbarProps) -> Html {
     let modal = use_context::<ModalSender>().unwrap();
+    let tr = use_translations();
+    let open_history = {
+        let modal = modal.clone();
+        Callback::from(move |_| {
+            modal.open(html! {
+              <HistoryDialog onclose={modal.close_callback()} />
+            });
+        })
+    };
+    let open_share_roster = {
+        let modal = modal.clone();
+        Callback::from(move |_| {
+            modal.open(html! {
+              <ShareRosterDialog onclose={modal.close_callback()} />
+            });
+        })
+    };
     let open_settings = Callback::from(move |_| {
         modal.open(html! {
           <SettingsDialog onclose={modal.close_callback()} />
         });
     });
+    let refresh = (!props.refreshing).then(|| props.onrefresh.reform(|_| ()));
     html! {
         <nav class="navbar">
           <div class="title">
-            <h1>{ "Pairandomizer" }</h1>
+            <h1>{ tr.get("app-title") }</h1>
           </div>
           <div class="controls">
-            <div class="ctrl" onclick={open_settings}>
+            <div
+              class={classes!("ctrl", props.refreshing.then_some("disabled"))}
+              title={tr.get("refresh-scenarios")}
+              onclick={refresh}
+              >
+              <span class="icon">{ "🔄" }</span>
+            </div>
+            <div class="ctrl" title={tr.get("open-history")} onclick={open_history}>
+              <span class="icon">{ "🕘" }</span>
+            </div>
+            <div class="ctrl" title={tr.get("open-share-roster")} onclick={open_share_roster}>
+              <span class="icon">{ "🔗" }</span>
+            </div>
+            <div class="ctrl" title={tr.get("open-settings")} onclick={open_settings}>
               <span class="icon">{ "⚙️" }</span>
             </div>
           </div>
@@ -0,0 +1,272 @@
+use std::rc::Rc;
+
+use bounce::prelude::*;
+use pairandomizer_core::{PickedScene, Randomization, TextFormat};
+use yew::prelude::*;
+
+use crate::{
+    app::{randomize_error_text, scenario_title},
+    history::{History, HistoryEntry},
+    i18n::use_translations,
+    link::link_url,
+    modal::{Dialog, ModalSender, Status, StatusLine},
+    qr::QrCodeDialog,
+    share::{copy_with_status, share_with_status},
+};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct ResultDialogProps {
+    /// The scene the result was randomized with, used again for re-rolling.
+    ///
+    /// Without it, the result can only be looked at.
+    pub picked: Option<Rc<PickedScene>>,
+    /// The result as stored in the history, which is updated on every change.
+    pub entry: HistoryEntry,
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Shows a result, letting the user re-roll single groups or all of them
+/// with the same scene.
+///
+/// Groups can be locked to keep them as they are when re-rolling the others.
+#[function_component(ResultDialog)]
+pub fn result_dialog(props: &ResultDialogProps) -> Html {
+    let modal = use_context::<ModalSender>().unwrap();
+    let tr = use_translations();
+    let history = use_atom::<History>();
+    let entry = {
+        let initial = props.entry.clone();
+        use_state(move || initial)
+    };
+    let result = &entry.result;
+    // Rows of the result that are kept when re-rolling
+    let locked = use_state(Vec::<usize>::new);
+    // The outcome of the last action that can fail, shown below the result
+    let status = use_state(|| None as Status);
+
+    // Shows a changed result and stores it in place of the old one
+    let update = {
+        let entry = entry.clone();
+        Callback::from(move |(seed, new_result): (u64, Randomization)| {
+            let new_entry = HistoryEntry::new(
+                entry.timestamp,
+                seed,
+                entry.group_size,
+                entry.names.clone(),
+                new_result,
+            );
+            history.set(history.with(new_entry.clone()));
+            entry.set(new_entry);
+        })
+    };
+
+    let new_message = {
+        let entry = entry.clone();
+        let update = update.clone();
+        let picked = props.picked.clone();
+        Callback::from(move |row: usize| {
+            let Some(picked) = &picked else {
+                return;
+            };
+            let mut new_result = entry.result.clone();
+            match new_result.reroll_message(&mut rand::thread_rng(), &picked.scene, row) {
+                Ok(()) => update.emit((entry.seed, new_result)),
+                Err(error) => gloo::console::error!(format!("Failed to re-roll: {error}")),
+            }
+        })
+    };
+    let repair = {
+        let entry = entry.clone();
+        let locked = locked.clone();
+        let update = update.clone();
+        Callback::from(move |row: usize| {
+            let mut new_result = entry.result.clone();
+            new_result.repair(&mut rand::thread_rng(), row, &locked);
+            update.emit((entry.seed, new_result));
+        })
+    };
+    let toggle_lock = {
+        let locked = locked.clone();
+        Callback::from(move |row: usize| {
+            let mut new_locked = Vec::clone(&locked);
+            match new_locked.iter().position(|locked_row| *locked_row == row) {
+                Some(ix) => {
+                    new_locked.remove(ix);
+                }
+                None => new_locked.push(row),
+            }
+            locked.set(new_locked);
+        })
+    };
+    let reroll_all = {
+        let entry = entry.clone();
+        let locked = locked.clone();
+        let status = status.clone();
+        let picked = props.picked.clone();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            let Some(picked) = &picked else {
+                return;
+            };
+            // Without locked groups, the result can be seeded again so links
+            // to it keep working
+            let rerolled = if locked.is_empty() {
+                let seed = rand::random();
+                picked
+                    .randomize_seeded(seed, &entry.names, entry.group_size)
+                    .map(|new_result| (seed, new_result))
+            } else {
+                let mut new_result = entry.result.clone();
+                new_result
+                    .reroll(
+                        &mut rand::thread_rng(),
+                        &picked.scene,
+                        &entry.names,
+                        entry.group_size,
+                        &locked,
+                    )
+                    .map(|()| (entry.seed, new_result))
+            };
+            match rerolled {
+                Ok(rerolled) => {
+                    update.emit(rerolled);
+                    status.set(None);
+                }
+                Err(error) => status.set(Some(Err(randomize_error_text(&tr, &error)))),
+            }
+        })
+    };
+    // Only results that the link gives again can be linked to, not ones
+    // changed group by group
+    let link = props.picked.as_ref().and_then(|picked| {
+        let link = entry.link();
+        link.reproduces(picked, result).then_some(link)
+    });
+    let url = link.map(|link| link_url(&link.to_fragment()));
+    let copy_link = url.clone().map(|url| {
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |_| copy_with_status(&tr, &status, url.clone(), "link-copied"))
+    });
+    // Comes back to the result as it is now, but without the locks
+    let show_qr_code = url.map(|url| {
+        let entry = entry.clone();
+        let picked = props.picked.clone();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            let back_to_result = {
+                let modal = modal.clone();
+                let entry = HistoryEntry::clone(&entry);
+                let picked = picked.clone();
+                Callback::from(move |_| {
+                    modal.open(html! {
+                      <ResultDialog
+                        picked={picked.clone()}
+                        entry={entry.clone()}
+                        onclose={modal.close_callback()}
+                        />
+                    })
+                })
+            };
+            modal.open(html! {
+              <QrCodeDialog
+                title={tr.get("result-link")}
+                url={url.clone()}
+                onclose={back_to_result}
+                />
+            });
+        })
+    });
+
+    let copy = {
+        let entry = entry.clone();
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |format: TextFormat| {
+            copy_with_status(&tr, &status, entry.result.format(format), "copied")
+        })
+    };
+    // Chat apps are where results are shared to most
+    let share = {
+        let entry = entry.clone();
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            share_with_status(
+                &tr,
+                &status,
+                entry.result.title(),
+                entry.result.format(TextFormat::Chat),
+                "copied",
+            )
+        })
+    };
+    let copy_buttons = TextFormat::ALL.into_iter().map(|format| {
+        let label = match format {
+            TextFormat::Plain => tr.get("copy-plain"),
+            TextFormat::Markdown => tr.get("copy-markdown"),
+            TextFormat::Chat => tr.get("copy-chat"),
+        };
+        html! { <button onclick={copy.reform(move |_| format)}>{ label }</button> }
+    });
+
+    let editable = props.picked.is_some();
+    // Re-pairing needs another unlocked group to swap names with
+    let unlocked_count = result.messages.len() - locked.len();
+    let rows = result.messages.iter().enumerate().map(|(row, msg)| {
+        let is_locked = locked.contains(&row);
+        let new_message = new_message.reform(move |_| row);
+        let repair = repair.reform(move |_| row);
+        let toggle_lock = toggle_lock.reform(move |_| row);
+        let (lock_icon, lock_title) = if is_locked {
+            ("🔒", tr.get("unlock-group"))
+        } else {
+            ("🔓", tr.get("lock-group"))
+        };
+        html! {
+          <li class={classes!(is_locked.then_some("locked"))}>
+            <span class="message">{ msg.to_string() }</span>
+            if editable {
+              <span class="actions">
+                <button onclick={toggle_lock} title={lock_title}>{ lock_icon }</button>
+                <button onclick={new_message} title={tr.get("new-message")}>{ "💬" }</button>
+                <button
+                  onclick={repair}
+                  title={tr.get("re-pair")}
+                  disabled={is_locked || unlocked_count < 2}
+                  >
+                  { "🔀" }
+                </button>
+              </span>
+            }
+          </li>
+        }
+    });
+    let title = scenario_title(&tr, &result.scenario) + " - " + &result.scene;
+    html! {
+      <Dialog {title}>
+        <ul class="result">{ for rows }</ul>
+        <div class="copy">
+          <button onclick={share}>{ tr.get("share") }</button>
+          { for copy_buttons }
+          if let Some(copy_link) = copy_link {
+            <button onclick={copy_link}>{ tr.get("copy-link") }</button>
+          }
+          if let Some(show_qr_code) = show_qr_code {
+            <button onclick={show_qr_code}>{ tr.get("show-qr-code") }</button>
+          }
+        </div>
+        <StatusLine status={(*status).clone()} />
+        <div class="buttons">
+          if editable {
+            <button onclick={reroll_all}>
+              { tr.get(if locked.is_empty() { "reroll-all" } else { "reroll-unlocked" }) }
+            </button>
+          } else {
+            <span class="status">{ tr.get("scene-unavailable") }</span>
+          }
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
+        </div>
+      </Dialog>
+    }
+}
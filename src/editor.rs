@@ -0,0 +1,372 @@
+use bounce::prelude::*;
+use pairandomizer_core::{
+    FormatVersion, RandomizedMsg, Scenario, ScenarioMeta, ScenarioSource, Scene, Severity,
+};
+use yew::prelude::*;
+
+use crate::{
+    app::{issue_text, severity_text, Names},
+    i18n::use_translations,
+    local_scenarios::LocalScenarios,
+    modal::Dialog,
+};
+
+/// Names used for the preview when there aren't enough real ones.
+const PREVIEW_NAMES: [&str; 3] = ["Alice", "Bob", "Carol"];
+
+/// A scenario as it is being edited.
+///
+/// The messages of a scene are kept as the raw text of their textarea, one
+/// message per line, so editing doesn't mess with the cursor.
+#[derive(Clone, PartialEq)]
+struct Draft {
+    meta: ScenarioMeta,
+    /// The scenario this draft was created from, so fields the editor doesn't
+    /// know about are kept.
+    base: Scenario,
+    lang: String,
+    scenes: Vec<SceneDraft>,
+    solo_messages: String,
+}
+#[derive(Clone, PartialEq)]
+struct SceneDraft {
+    /// The scene this draft was created from, so fields the editor doesn't
+    /// know about are kept.
+    base: Scene,
+    name: String,
+    messages: String,
+    triple_messages: String,
+    solo_messages: String,
+}
+impl Draft {
+    fn new() -> Self {
+        Self {
+            meta: ScenarioMeta {
+                name: String::new(),
+                lang: None,
+                languages: Vec::new(),
+                filename: format!("editor-{}.json", js_sys::Date::now() as u64),
+                hash: None,
+                beta: false,
+                source: ScenarioSource::Local,
+            },
+            base: Scenario::default(),
+            lang: String::new(),
+            scenes: vec![SceneDraft::from_scene(Scene::default())],
+            solo_messages: String::new(),
+        }
+    }
+    fn from_scenario(meta: &ScenarioMeta, scenario: &Scenario) -> Self {
+        Self {
+            meta: meta.clone(),
+            base: scenario.clone(),
+            lang: meta.lang.clone().unwrap_or_default(),
+            scenes: scenario
+                .scenes
+                .iter()
+                .cloned()
+                .map(SceneDraft::from_scene)
+                .collect(),
+            solo_messages: scenario.solo_messages.join("\n"),
+        }
+    }
+    fn to_scenario(&self) -> (ScenarioMeta, Scenario) {
+        let lang = self.lang.trim();
+        let mut meta = ScenarioMeta {
+            name: self.meta.name.trim().to_string(),
+            lang: (!lang.is_empty()).then(|| lang.to_string()),
+            ..self.meta.clone()
+        };
+        let mut scenario = self.base.clone();
+        scenario.format_version = FormatVersion::CURRENT;
+        scenario.scenes = self.scenes.iter().map(SceneDraft::to_scene).collect();
+        scenario.solo_messages = lines(&self.solo_messages);
+        meta.add_languages_of(&scenario);
+        (meta, scenario)
+    }
+}
+impl SceneDraft {
+    fn from_scene(scene: Scene) -> Self {
+        Self {
+            name: scene.name.clone(),
+            messages: scene.messages.join("\n"),
+            triple_messages: scene.triple_messages.join("\n"),
+            solo_messages: scene.solo_messages.join("\n"),
+            base: scene,
+        }
+    }
+    fn to_scene(&self) -> Scene {
+        let mut scene = self.base.clone();
+        scene.name = self.name.trim().to_string();
+        scene.messages = lines(&self.messages);
+        scene.triple_messages = lines(&self.triple_messages);
+        scene.solo_messages = lines(&self.solo_messages);
+        scene
+    }
+}
+
+/// The messages in the text of a textarea, skipping empty lines.
+fn lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The names to preview messages for groups of `count` with, taking the
+/// real ones if there are enough.
+fn preview_names(names: &[String], count: usize) -> Vec<String> {
+    if names.len() >= count {
+        names[..count].to_vec()
+    } else {
+        PREVIEW_NAMES[..count]
+            .iter()
+            .map(|n| n.to_string())
+            .collect()
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ScenarioEditorProps {
+    /// The local scenario to edit, or None to create a new one.
+    pub index: Option<usize>,
+    pub onclose: Callback<()>,
+}
+
+/// A dialog for creating and editing local scenarios.
+#[function_component(ScenarioEditor)]
+pub fn scenario_editor(props: &ScenarioEditorProps) -> Html {
+    let tr = use_translations();
+    let local = use_atom::<LocalScenarios>();
+    let names = use_atom_value::<Names>();
+    let draft = {
+        let local = local.clone();
+        let index = props.index;
+        use_state(move || {
+            index
+                .and_then(|ix| local.0.get(ix))
+                .map(|(meta, scenario)| Draft::from_scenario(meta, scenario))
+                .unwrap_or_else(Draft::new)
+        })
+    };
+    let selected_scene = use_state(|| 0);
+
+    let (meta, scenario) = draft.to_scenario();
+    let issues = scenario.validate();
+    let can_save =
+        !meta.name.is_empty() && issues.iter().all(|issue| issue.severity != Severity::Error);
+
+    let update_name = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            let mut new_draft = Draft::clone(&draft);
+            new_draft.meta.name = input.value();
+            draft.set(new_draft);
+        })
+    };
+    let update_lang = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            let mut new_draft = Draft::clone(&draft);
+            new_draft.lang = input.value();
+            draft.set(new_draft);
+        })
+    };
+    let update_solo_messages = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let ta = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>();
+            let mut new_draft = Draft::clone(&draft);
+            new_draft.solo_messages = ta.value();
+            draft.set(new_draft);
+        })
+    };
+    let add_scene = {
+        let draft = draft.clone();
+        let selected_scene = selected_scene.clone();
+        Callback::from(move |_| {
+            let mut new_draft = Draft::clone(&draft);
+            new_draft
+                .scenes
+                .push(SceneDraft::from_scene(Scene::default()));
+            selected_scene.set(new_draft.scenes.len() - 1);
+            draft.set(new_draft);
+        })
+    };
+    let save = {
+        let local = local.clone();
+        let onclose = props.onclose.clone();
+        let index = props.index;
+        let entry = (meta.clone(), scenario.clone());
+        Callback::from(move |_| {
+            let mut scenarios = local.0.clone();
+            match index.and_then(|ix| scenarios.get_mut(ix)) {
+                Some(existing) => *existing = entry.clone(),
+                None => scenarios.push(entry.clone()),
+            }
+            local.set(LocalScenarios(scenarios));
+            onclose.emit(());
+        })
+    };
+
+    let scenes = draft.scenes.iter().enumerate().map(|(ix, scene)| {
+        let update_scene_name = {
+            let draft = draft.clone();
+            Callback::from(move |e: InputEvent| {
+                let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+                let mut new_draft = Draft::clone(&draft);
+                new_draft.scenes[ix].name = input.value();
+                draft.set(new_draft);
+            })
+        };
+        let update_text = |field: fn(&mut SceneDraft) -> &mut String| {
+            let draft = draft.clone();
+            Callback::from(move |e: InputEvent| {
+                let ta = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>();
+                let mut new_draft = Draft::clone(&draft);
+                *field(&mut new_draft.scenes[ix]) = ta.value();
+                draft.set(new_draft);
+            })
+        };
+        let update_messages = update_text(|scene| &mut scene.messages);
+        let update_triple_messages = update_text(|scene| &mut scene.triple_messages);
+        let update_solo_messages = update_text(|scene| &mut scene.solo_messages);
+        let remove_scene = {
+            let draft = draft.clone();
+            let selected_scene = selected_scene.clone();
+            Callback::from(move |_| {
+                let mut new_draft = Draft::clone(&draft);
+                new_draft.scenes.remove(ix);
+                selected_scene.set(0);
+                draft.set(new_draft);
+            })
+        };
+        let select = {
+            let selected_scene = selected_scene.clone();
+            Callback::from(move |_: FocusEvent| selected_scene.set(ix))
+        };
+        html! {
+          <div class={classes!("scene", (*selected_scene == ix).then_some("selected"))}>
+            <div class="setting">
+              <input
+                type="text"
+                placeholder={tr.get("scene-name")}
+                value={scene.name.clone()}
+                oninput={update_scene_name}
+                onfocus={select.clone()}
+                />
+              <button onclick={remove_scene}>{ tr.get("remove") }</button>
+            </div>
+            <textarea
+              placeholder={tr.get("messages-placeholder")}
+              value={scene.messages.clone()}
+              oninput={update_messages}
+              onfocus={select.clone()}
+              />
+            <textarea
+              placeholder={tr.get("triple-messages-placeholder")}
+              value={scene.triple_messages.clone()}
+              oninput={update_triple_messages}
+              onfocus={select.clone()}
+              />
+            <textarea
+              placeholder={tr.get("solo-messages-placeholder")}
+              value={scene.solo_messages.clone()}
+              oninput={update_solo_messages}
+              onfocus={select}
+              />
+          </div>
+        }
+    });
+
+    // Preview every message of the selected scene with the current names,
+    // as many as the message is meant for
+    let preview = scenario
+        .scenes
+        .get(*selected_scene)
+        .map(|scene| {
+            // Scenes without solo messages of their own use the scenario's
+            let solo_messages = if scene.solo_messages.is_empty() {
+                &scenario.solo_messages
+            } else {
+                &scene.solo_messages
+            };
+            [
+                (&scene.messages, 2),
+                (&scene.triple_messages, 3),
+                (solo_messages, 1),
+            ]
+            .into_iter()
+            .flat_map(|(messages, count)| {
+                let names = preview_names(&names.0, count);
+                messages.iter().map(move |message| {
+                    let rendered = RandomizedMsg {
+                        message: message.clone(),
+                        names: names.clone(),
+                    }
+                    .to_string();
+                    html! {
+                      <li>{ rendered }</li>
+                    }
+                })
+            })
+            .collect::<Html>()
+        })
+        .unwrap_or_default();
+
+    let close = props.onclose.reform(|_| ());
+    html! {
+      <Dialog title={tr.get(if props.index.is_some() { "edit-scenario" } else { "new-scenario" })}>
+        <div class="settings editor">
+          <div class="setting">
+            <span class="name">{ tr.get("name") }</span>
+            <input type="text" value={draft.meta.name.clone()} oninput={update_name} />
+          </div>
+          <div class="setting">
+            <span class="name">{ tr.get("language") }</span>
+            <input
+              type="text"
+              placeholder={tr.get("language-placeholder")}
+              value={draft.lang.clone()}
+              oninput={update_lang}
+              />
+          </div>
+          <h2>{ tr.get("scenes") }</h2>
+          { for scenes }
+          <div class="setting">
+            <span />
+            <button onclick={add_scene}>{ tr.get("add-scene") }</button>
+          </div>
+          <h2>{ tr.get("scenario-solo-messages") }</h2>
+          <textarea
+            placeholder={tr.get("scenario-solo-messages-placeholder")}
+            value={draft.solo_messages.clone()}
+            oninput={update_solo_messages}
+            />
+          <h2>{ tr.get("preview") }</h2>
+          <ul class="preview">{ preview }</ul>
+          if meta.name.is_empty() {
+            <span class="status error">{ tr.get("scenario-needs-name") }</span>
+          }
+          { for issues.iter().map(|issue| {
+              let class = match issue.severity {
+                  Severity::Warning => "status warning",
+                  Severity::Error => "status error",
+              };
+              html! {
+                <span {class} title={severity_text(&tr, issue.severity)}>
+                  { issue_text(&tr, issue) }
+                </span>
+              }
+          }) }
+        </div>
+        <div class="buttons">
+          <button onclick={close}>{ tr.get("cancel") }</button>
+          <button onclick={save} disabled={!can_save}>{ tr.get("save") }</button>
+        </div>
+      </Dialog>
+    }
+}
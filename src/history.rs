@@ -0,0 +1,249 @@
+use std::rc::Rc;
+
+use bounce::prelude::*;
+use gloo::storage::{LocalStorage, Storage};
+use pairandomizer_core::{LoadedIndex, Randomization, ResultLink};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::{
+    app::{format_timestamp, scenario_title, AvailableIndex, ResultDialog},
+    i18n::use_translations,
+    language::Languages,
+    modal::{Dialog, ModalSender},
+    statistics::StatisticsDialog,
+};
+
+const STORAGE_KEY: &str = "history";
+/// How many results are kept; the oldest are dropped first.
+const MAX_ENTRIES: usize = 500;
+
+/// A result as it was generated, or last re-rolled.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// When the result was generated, in milliseconds since the unix epoch.
+    ///
+    /// Also identifies the entry.
+    pub timestamp: f64,
+    /// The seed the result was randomized with.
+    ///
+    /// Results changed group by group can't be randomized again from it.
+    pub seed: u64,
+    pub group_size: usize,
+    /// The names in the order they were randomized in, which randomizing
+    /// them again with the seed depends on.
+    #[serde(default)]
+    pub names: Vec<String>,
+    pub result: Randomization,
+    /// The messages with the names filled in, as they were shown.
+    pub messages: Vec<String>,
+}
+impl HistoryEntry {
+    pub fn new(
+        timestamp: f64,
+        seed: u64,
+        group_size: usize,
+        names: Vec<String>,
+        result: Randomization,
+    ) -> Self {
+        let messages = result.messages.iter().map(ToString::to_string).collect();
+        Self {
+            timestamp,
+            seed,
+            group_size,
+            names,
+            result,
+            messages,
+        }
+    }
+    /// A link to randomize the result again.
+    pub fn link(&self) -> ResultLink {
+        ResultLink::new(self.seed, &self.result, &self.names, self.group_size)
+    }
+}
+
+/// All results generated on this device, oldest first.
+#[derive(PartialEq, Atom)]
+#[bounce(observed)]
+pub struct History(pub Vec<HistoryEntry>);
+impl History {
+    /// Adds an entry, or replaces the one with the same timestamp.
+    pub fn with(&self, entry: HistoryEntry) -> Self {
+        let mut entries = self.0.clone();
+        match entries.iter_mut().find(|e| e.timestamp == entry.timestamp) {
+            Some(existing) => *existing = entry,
+            None => entries.push(entry),
+        }
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        Self(entries)
+    }
+}
+impl Default for History {
+    fn default() -> Self {
+        let mut entries: Vec<HistoryEntry> = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        // Entries stored before the names were kept still have them in groups
+        for entry in entries.iter_mut().filter(|entry| entry.names.is_empty()) {
+            entry.names = entry
+                .result
+                .messages
+                .iter()
+                .flat_map(|msg| msg.names.iter().cloned())
+                .collect();
+        }
+        Self(entries)
+    }
+}
+impl Observed for History {
+    fn changed(self: Rc<Self>) {
+        if let Err(error) = LocalStorage::set(STORAGE_KEY, &self.0) {
+            gloo::console::error!(format!("Failed to save history: {:?}", error));
+        }
+    }
+}
+
+/// Opens a stored result in the result dialog, returning to the history once
+/// it is closed.
+fn reopen(modal: &ModalSender, index: Option<&LoadedIndex>, entry: &HistoryEntry) {
+    let result = &entry.result;
+    // Re-rolling needs the scene, which may have been removed since
+    let picked = index
+        .and_then(|index| {
+            index.find_scene(
+                &result.scenario.filename,
+                &result.scene,
+                result.lang.as_deref(),
+            )
+        })
+        .map(Rc::new);
+    let back_to_history = {
+        let modal = modal.clone();
+        Callback::from(move |_| {
+            modal.open(html! {
+              <HistoryDialog onclose={modal.close_callback()} />
+            })
+        })
+    };
+    modal.open(html! {
+      <ResultDialog {picked} entry={entry.clone()} onclose={back_to_history} />
+    });
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct HistoryDialogProps {
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Lists the stored results, newest first.
+#[function_component(HistoryDialog)]
+pub fn history_dialog(props: &HistoryDialogProps) -> Html {
+    let tr = use_translations();
+    let languages = use_context::<Languages>().unwrap();
+    let modal = use_context::<ModalSender>().unwrap();
+    let history = use_atom::<History>();
+    let index = use_selector_value::<AvailableIndex>();
+    let search = use_state(String::new);
+
+    let update_search = {
+        let search = search.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            search.set(input.value());
+        })
+    };
+
+    let open_statistics = {
+        let modal = modal.clone();
+        Callback::from(move |_| {
+            let back_to_history = {
+                let modal = modal.clone();
+                Callback::from(move |_| {
+                    modal.open(html! {
+                      <HistoryDialog onclose={modal.close_callback()} />
+                    })
+                })
+            };
+            modal.open(html! {
+              <StatisticsDialog onclose={back_to_history} />
+            });
+        })
+    };
+
+    let query = search.trim().to_lowercase();
+    let entries = history
+        .0
+        .iter()
+        .rev()
+        .filter(|entry| {
+            query.is_empty()
+                || entry
+                    .result
+                    .messages
+                    .iter()
+                    .flat_map(|msg| &msg.names)
+                    .any(|name| name.to_lowercase().contains(&query))
+        })
+        .map(|entry| {
+            let timestamp = entry.timestamp;
+            let remove = {
+                let history = history.clone();
+                Callback::from(move |_| {
+                    let mut entries = history.0.clone();
+                    entries.retain(|e| e.timestamp != timestamp);
+                    history.set(History(entries));
+                })
+            };
+            let open = {
+                let modal = modal.clone();
+                let index = index.clone();
+                let entry = entry.clone();
+                Callback::from(move |_| reopen(&modal, Option::as_ref(&index), &entry))
+            };
+            let messages = entry.messages.iter().map(|msg| html! { <li>{ msg }</li> });
+            html! {
+              <div class="entry">
+                <div class="header">
+                  <span class="name">
+                    { scenario_title(&tr, &entry.result.scenario) + " - " + &entry.result.scene }
+                  </span>
+                  <small>{ format_timestamp(timestamp, languages.primary()) }</small>
+                </div>
+                <ul>{ for messages }</ul>
+                <span class="actions">
+                  <button onclick={open}>{ tr.get("open") }</button>
+                  <button onclick={remove}>{ tr.get("remove") }</button>
+                </span>
+              </div>
+            }
+        })
+        .collect::<Vec<_>>();
+    let empty = entries.is_empty().then(|| {
+        let text = if history.0.is_empty() {
+            tr.get("history-empty")
+        } else {
+            tr.get("history-no-matches")
+        };
+        html! { <span class="status">{ text }</span> }
+    });
+
+    html! {
+      <Dialog title={tr.get("history")}>
+        <div class="history">
+          <input
+            type="search"
+            placeholder={tr.get("search-by-name")}
+            value={(*search).clone()}
+            oninput={update_search}
+            />
+          { for entries }
+          { empty.unwrap_or_default() }
+        </div>
+        <div class="buttons">
+          <button onclick={open_statistics} disabled={history.0.is_empty()}>
+            { tr.get("statistics") }
+          </button>
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
+        </div>
+      </Dialog>
+    }
+}
@@ -0,0 +1,94 @@
+//! Translations of the user interface, using Fluent.
+
+use std::rc::Rc;
+
+use fluent::{FluentArgs, FluentBundle, FluentResource};
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use unic_langid::{langid, LanguageIdentifier};
+use yew::prelude::*;
+
+/// The translations shipped with the app.
+const SOURCES: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+/// The translations for the user's languages, provided as a context by
+/// [`crate::language::LanguageProvider`].
+///
+/// Messages missing from the best matching translation are looked up in the
+/// next one, ending with English.
+#[derive(Clone)]
+pub struct Translations(Rc<Vec<FluentBundle<FluentResource>>>);
+impl PartialEq for Translations {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Translations {
+    pub fn new(languages: &[LanguageIdentifier]) -> Self {
+        let sources = SOURCES
+            .iter()
+            .filter_map(|(lang, source)| Some((lang.parse::<LanguageIdentifier>().ok()?, *source)))
+            .collect::<Vec<_>>();
+        let available = sources
+            .iter()
+            .map(|(lang, _)| lang.clone())
+            .collect::<Vec<_>>();
+        let default = langid!("en");
+        let negotiated = negotiate_languages(
+            languages,
+            &available,
+            Some(&default),
+            NegotiationStrategy::Filtering,
+        );
+        let bundles = negotiated
+            .into_iter()
+            .filter_map(|lang| {
+                let (_, source) = sources.iter().find(|(available, _)| available == lang)?;
+                let resource = FluentResource::try_new(source.to_string())
+                    .map_err(|(_, errors)| {
+                        gloo::console::error!(format!(
+                            "Errors in the {lang} translation: {errors:?}"
+                        ))
+                    })
+                    .ok()?;
+                let mut bundle = FluentBundle::new(vec![lang.clone()]);
+                // The isolation marks only make sense for right-to-left scripts
+                bundle.set_use_isolating(false);
+                bundle.add_resource(resource).ok()?;
+                Some(bundle)
+            })
+            .collect();
+        Self(Rc::new(bundles))
+    }
+
+    /// Translates the message `id`.
+    pub fn get(&self, id: &str) -> String {
+        self.format(id, None)
+    }
+
+    /// Translates the message `id`, filling in `args`.
+    ///
+    /// Returns the id itself if no translation has the message.
+    pub fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in self.0.iter() {
+            if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, args, &mut errors);
+                if !errors.is_empty() {
+                    gloo::console::error!(format!("Errors formatting {id}: {errors:?}"));
+                }
+                return text.into_owned();
+            }
+        }
+        gloo::console::error!(format!("Missing translation: {id}"));
+        id.to_string()
+    }
+}
+
+/// The translations for the user's languages.
+#[hook]
+pub fn use_translations() -> Translations {
+    use_context::<Translations>().unwrap()
+}
@@ -0,0 +1,80 @@
+//! The languages the user prefers, taken from the browser unless they picked
+//! one in the settings.
+
+use std::rc::Rc;
+
+use bounce::prelude::*;
+use pairandomizer_core::fallback_chain;
+use unic_langid::{langid, LanguageIdentifier};
+use yew::prelude::*;
+
+use crate::{i18n::Translations, settings::Settings};
+
+/// The user's languages, provided as a context by [`LanguageProvider`].
+#[derive(Clone, PartialEq)]
+pub struct Languages {
+    primary: LanguageIdentifier,
+    chain: Rc<Vec<LanguageIdentifier>>,
+}
+impl Languages {
+    fn new(preferred: &[LanguageIdentifier]) -> Self {
+        Self {
+            primary: preferred
+                .first()
+                .cloned()
+                .unwrap_or_else(|| langid!("en-US")),
+            chain: Rc::new(fallback_chain(preferred)),
+        }
+    }
+    /// The most preferred language, used for formatting.
+    pub fn primary(&self) -> &LanguageIdentifier {
+        &self.primary
+    }
+    /// All languages to look for scenarios in, most preferred first.
+    pub fn chain(&self) -> &[LanguageIdentifier] {
+        &self.chain
+    }
+}
+
+/// The languages set in the browser, most preferred first.
+pub fn browser_languages() -> Vec<LanguageIdentifier> {
+    let navigator = gloo::utils::window().navigator();
+    let mut languages = navigator
+        .languages()
+        .iter()
+        .filter_map(|lang| lang.as_string()?.parse().ok())
+        .collect::<Vec<_>>();
+    if languages.is_empty() {
+        languages.extend(navigator.language().and_then(|lang| lang.parse().ok()));
+    }
+    languages
+}
+
+#[derive(PartialEq, Properties)]
+pub struct LanguageProviderProps {
+    pub children: Html,
+}
+
+/// Provides the [`Languages`] and [`Translations`] contexts, following the
+/// language setting.
+#[function_component(LanguageProvider)]
+pub fn language_provider(props: &LanguageProviderProps) -> Html {
+    let settings = use_atom_value::<Settings>();
+    let browser = use_memo((), |_| browser_languages());
+    let languages = use_memo(settings.language.clone(), |language| {
+        match language.as_deref().and_then(|lang| lang.parse().ok()) {
+            Some(language) => Languages::new(&[language]),
+            None => Languages::new(&browser),
+        }
+    });
+    let translations = use_memo(languages.clone(), |languages| {
+        Translations::new(languages.chain())
+    });
+    html! {
+      <ContextProvider<Languages> context={(*languages).clone()}>
+        <ContextProvider<Translations> context={(*translations).clone()}>
+          { props.children.clone() }
+        </ContextProvider<Translations>>
+      </ContextProvider<Languages>>
+    }
+}
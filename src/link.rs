@@ -0,0 +1,160 @@
+//! Opening the app through a link that carries something to show in its
+//! URL fragment.
+
+use std::rc::Rc;
+
+use bounce::prelude::*;
+use fluent::fluent_args;
+use pairandomizer_core::{ResultLink, RosterLink};
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::{
+    app::{error_text, AvailableIndex, CurrentIndex, IndexLoadError, ResultDialog},
+    history::HistoryEntry,
+    i18n::use_translations,
+    modal::{Dialog, ModalSender},
+    roster::ImportRosterDialog,
+};
+
+/// The URL of the app with `fragment`, to share with others.
+pub fn link_url(fragment: &str) -> String {
+    let location = gloo::utils::window().location();
+    format!(
+        "{}{}#{fragment}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default()
+    )
+}
+
+/// The fragment of the current URL, without the `#`, if there is one.
+pub fn current_fragment() -> Option<String> {
+    let hash = gloo::utils::window().location().hash().ok()?;
+    let fragment = hash.strip_prefix('#').unwrap_or(&hash);
+    (!fragment.is_empty()).then(|| fragment.to_string())
+}
+
+/// Removes the fragment from the URL, so reloading doesn't open it again.
+fn clear_fragment() {
+    let location = gloo::utils::window().location();
+    let url = location.pathname().unwrap_or_default() + &location.search().unwrap_or_default();
+    if let Err(error) =
+        gloo::utils::history().replace_state_with_url(&JsValue::NULL, "", Some(&url))
+    {
+        gloo::console::error!(format!("Failed to clear the link: {:?}", error));
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct LinkHandlerProps {
+    /// The URL fragment the app was opened with, if it wasn't handled yet.
+    pub fragment: Option<AttrValue>,
+    /// Called once the fragment was handled.
+    pub onhandled: Callback<()>,
+}
+
+/// Shows what a link the app was opened with points to, as soon as the
+/// scenarios are loaded if it needs them.
+///
+/// Result links wait for the scenarios to be loaded from the server, as the
+/// cached ones may not have the linked scene yet.
+#[function_component(LinkHandler)]
+pub fn link_handler(props: &LinkHandlerProps) -> Html {
+    let modal = use_context::<ModalSender>().unwrap();
+    let tr = use_translations();
+    let current = use_atom_value::<CurrentIndex>();
+    let index = use_selector_value::<AvailableIndex>();
+    let load_error = use_atom_value::<IndexLoadError>();
+
+    let fragment = props.fragment.clone();
+    let live = current.live;
+    let loaded = index.is_some();
+    let onhandled = props.onhandled.clone();
+    let deps = (fragment, live, loaded, load_error);
+    use_effect_with(deps, move |(fragment, live, _, load_error)| {
+        let Some(fragment) = fragment else {
+            return;
+        };
+        let show_error = |error: String| {
+            modal.open(html! {
+              <Dialog title={tr.get("link-invalid")}>
+                <div>{ tr.format("status-error", Some(&fluent_args!["error" => error])) }</div>
+                <div class="buttons">
+                  <span />
+                  <button onclick={modal.close_callback()}>{ tr.get("close") }</button>
+                </div>
+              </Dialog>
+            })
+        };
+        let malformed = |error: anyhow::Error| {
+            tr.format(
+                "link-malformed",
+                Some(&fluent_args!["error" => format!("{error:#}")]),
+            )
+        };
+        // Importing names doesn't have to wait for the scenarios, the dialog
+        // shows the selected one once they are loaded
+        if let Some(link) = RosterLink::from_fragment(fragment) {
+            match link {
+                Ok(link) => modal.open(html! {
+                  <ImportRosterDialog {link} onclose={modal.close_callback()} />
+                }),
+                Err(error) => show_error(malformed(error)),
+            }
+            clear_fragment();
+            onhandled.emit(());
+            return;
+        }
+        // After a failed load, the cached or local scenarios may still have
+        // the linked scene
+        if !live && load_error.0.is_none() {
+            return;
+        }
+        let Some(index) = &**index else {
+            // Without any scenarios the link can't be opened, but the user
+            // should still learn about it
+            if let Some(error) = &load_error.0 {
+                show_error(tr.format(
+                    "link-scenarios-unavailable",
+                    Some(&fluent_args!["error" => error_text(&tr, error)]),
+                ));
+                clear_fragment();
+                onhandled.emit(());
+            }
+            return;
+        };
+        match ResultLink::from_fragment(fragment) {
+            Some(Ok(link)) => match link.regenerate(index) {
+                // Results from links aren't added to the history unless they
+                // are changed, so viewing them doesn't skew the statistics
+                Ok((picked, result)) => {
+                    let entry = HistoryEntry::new(
+                        js_sys::Date::now(),
+                        link.seed,
+                        link.group_size,
+                        link.names,
+                        result,
+                    );
+                    modal.open(html! {
+                      <ResultDialog
+                        picked={Rc::new(picked)}
+                        {entry}
+                        onclose={modal.close_callback()}
+                        />
+                    });
+                }
+                Err(error) => show_error(error_text(&tr, &error)),
+            },
+            Some(Err(error)) => show_error(malformed(error)),
+            // Not a link to anything, maybe meant for someone else
+            None => {
+                onhandled.emit(());
+                return;
+            }
+        }
+        clear_fragment();
+        onhandled.emit(());
+    });
+
+    Html::default()
+}
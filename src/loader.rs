@@ -0,0 +1,160 @@
+use std::{collections::HashMap, fmt};
+
+use anyhow::{bail, Context, Result};
+use gloo::net::http::Request;
+use pairandomizer_core::{parse_versioned, Index, LoadedIndex, INDEX_FILENAME};
+use serde::de::DeserializeOwned;
+
+/// Why a file couldn't be fetched from the server.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The server couldn't be reached, e.g. because the device is offline.
+    Unreachable { url: String, error: String },
+    /// The server answered with an error status.
+    Status {
+        url: String,
+        status: u16,
+        text: String,
+    },
+}
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable { url, error } => write!(f, "{url}: {error}"),
+            Self::Status { url, status, text } => write!(f, "{url}: {status} {text}"),
+        }
+    }
+}
+impl std::error::Error for FetchError {}
+
+/// The outcome of a conditional request.
+enum Fetched<T> {
+    Modified { data: T, etag: Option<String> },
+    NotModified,
+}
+
+/// Fetches and parses `url`, unless the server reports that the resource
+/// still matches `etag`.
+///
+/// Note that for cross-origin servers, the ETag header is only visible if it
+/// is listed in `Access-Control-Expose-Headers`.
+async fn fetch_if_changed<T: DeserializeOwned>(
+    url: &str,
+    etag: Option<&str>,
+) -> Result<Fetched<T>> {
+    let mut request = Request::get(url);
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|error| FetchError::Unreachable {
+            url: url.to_string(),
+            error: error.to_string(),
+        })?;
+    if response.status() == 304 {
+        return Ok(Fetched::NotModified);
+    }
+    if !response.ok() {
+        return Err(FetchError::Status {
+            url: url.to_string(),
+            status: response.status(),
+            text: response.status_text(),
+        }
+        .into());
+    }
+    let etag = response.headers().get("etag");
+    let data = parse_versioned(&response.text().await?).with_context(|| url.to_string())?;
+    Ok(Fetched::Modified { data, etag })
+}
+
+/// Loads the index and all scenarios it references from a server.
+///
+/// Files that didn't change since `previous` was loaded are taken from there
+/// instead of being downloaded again.
+pub async fn load(server_url: &str, previous: Option<&LoadedIndex>) -> Result<LoadedIndex> {
+    let mut etags = HashMap::new();
+    let index = fetch_index(server_url, previous, &mut etags).await?;
+    load_scenarios(index, server_url, previous, etags).await
+}
+
+pub async fn fetch_index(
+    server_url: &str,
+    previous: Option<&LoadedIndex>,
+    etags: &mut HashMap<String, String>,
+) -> Result<Index> {
+    let previous_etag = previous.and_then(|previous| previous.etags.get(INDEX_FILENAME));
+    let url = format!("{server_url}/res/json/{INDEX_FILENAME}");
+    match (
+        fetch_if_changed(&url, previous_etag.map(String::as_str)).await?,
+        previous,
+    ) {
+        (Fetched::Modified { data, etag }, _) => {
+            etags.extend(etag.map(|etag| (INDEX_FILENAME.to_string(), etag)));
+            Ok(data)
+        }
+        (Fetched::NotModified, Some(previous)) => {
+            etags.extend(previous_etag.map(|etag| (INDEX_FILENAME.to_string(), etag.clone())));
+            Ok(previous.inner.clone())
+        }
+        (Fetched::NotModified, None) => bail!("{url}: unexpected 304 Not Modified"),
+    }
+}
+
+pub async fn load_scenarios(
+    index: Index,
+    server_url: &str,
+    previous: Option<&LoadedIndex>,
+    etags: HashMap<String, String>,
+) -> Result<LoadedIndex> {
+    let metas = index.all_scenarios().collect::<Vec<_>>();
+    let mut loaded = LoadedIndex::new(index);
+    loaded.etags = etags;
+    for mut meta in metas {
+        let previous_scenario = previous.and_then(|previous| {
+            previous
+                .scenarios
+                .iter()
+                .find(|(m, _)| m.filename == meta.filename)
+                .map(|(m, scenario)| (previous, m, scenario))
+        });
+        let unchanged = previous_scenario.is_some_and(|(_, previous_meta, _)| {
+            meta.hash.is_some() && meta.hash == previous_meta.hash
+        });
+        let previous_etag =
+            previous_scenario.and_then(|(previous, _, _)| previous.etags.get(&meta.filename));
+        let url = format!("{server_url}/res/json/{}", meta.filename);
+        let fetched = if unchanged {
+            Fetched::NotModified
+        } else {
+            fetch_if_changed(&url, previous_etag.map(String::as_str)).await?
+        };
+        match (fetched, previous_scenario) {
+            (Fetched::Modified { data, etag }, _) => {
+                loaded
+                    .etags
+                    .extend(etag.map(|etag| (meta.filename.clone(), etag)));
+                loaded.add_scenario(meta, data);
+            }
+            (Fetched::NotModified, Some((previous, _, scenario))) => {
+                // The previous copy has already been validated
+                loaded
+                    .etags
+                    .extend(previous_etag.map(|etag| (meta.filename.clone(), etag.clone())));
+                loaded.issues.extend(
+                    previous
+                        .issues
+                        .iter()
+                        .filter(|(filename, _)| *filename == meta.filename)
+                        .cloned(),
+                );
+                // The index may not list the languages of translations
+                meta.add_languages_of(scenario);
+                loaded.scenarios.push((meta, scenario.clone()));
+            }
+            (Fetched::NotModified, None) => bail!("{url}: unexpected 304 Not Modified"),
+        }
+    }
+    Ok(loaded)
+}
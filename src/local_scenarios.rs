@@ -0,0 +1,190 @@
+use std::{fmt, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use bounce::prelude::*;
+use fluent::fluent_args;
+use gloo::storage::{LocalStorage, Storage};
+use itertools::Itertools;
+use pairandomizer_core::{
+    parse_versioned, Issue, Scenario, ScenarioMeta, ScenarioSource, Severity,
+};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::{
+    app::{error_text, issue_text},
+    editor::ScenarioEditor,
+    i18n::use_translations,
+    modal::{ModalSender, Status, StatusLine},
+    settings::SettingsDialog,
+};
+
+const STORAGE_KEY: &str = "local_scenarios";
+
+/// Scenarios that were added on this device instead of coming from a server.
+#[derive(PartialEq, Atom)]
+#[bounce(observed)]
+pub struct LocalScenarios(pub Vec<(ScenarioMeta, Scenario)>);
+impl Default for LocalScenarios {
+    fn default() -> Self {
+        Self(LocalStorage::get(STORAGE_KEY).unwrap_or_default())
+    }
+}
+impl Observed for LocalScenarios {
+    fn changed(self: Rc<Self>) {
+        if let Err(error) = LocalStorage::set(STORAGE_KEY, &self.0) {
+            gloo::console::error!(format!("Failed to save local scenarios: {:?}", error));
+        }
+    }
+}
+
+/// The errors that keep a scenario file from being imported.
+#[derive(Debug)]
+struct InvalidScenario(Vec<Issue>);
+impl fmt::Display for InvalidScenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join("; "))
+    }
+}
+impl std::error::Error for InvalidScenario {}
+
+/// Parses and validates a scenario file picked by the user, returning it
+/// along with any warnings.
+///
+/// The name and language are taken from the file name, following the
+/// `name_lang.json` convention used on the server, and further languages
+/// from the translations in the file.
+fn import(filename: &str, json: &str) -> Result<(ScenarioMeta, Scenario, Vec<Issue>)> {
+    let scenario: Scenario = parse_versioned(json)?;
+    let (errors, warnings): (Vec<_>, Vec<_>) = scenario
+        .validate()
+        .into_iter()
+        .partition(|issue| issue.severity == Severity::Error);
+    if !errors.is_empty() {
+        return Err(InvalidScenario(errors).into());
+    }
+    let mut meta = ScenarioMeta::from_filename(filename, ScenarioSource::Local);
+    meta.add_languages_of(&scenario);
+    Ok((meta, scenario, warnings))
+}
+
+/// The part of the settings dialog managing local scenarios.
+#[function_component(LocalScenariosSettings)]
+pub fn local_scenarios_settings() -> Html {
+    let modal = use_context::<ModalSender>().unwrap();
+    let tr = use_translations();
+    let local = use_atom::<LocalScenarios>();
+    let status = use_state(|| None as Status);
+
+    // Opens the editor, returning to the settings once it is closed
+    let open_editor = Callback::from(move |index: Option<usize>| {
+        let back_to_settings = {
+            let modal = modal.clone();
+            Callback::from(move |_| {
+                modal.open(html! {
+                  <SettingsDialog onclose={modal.close_callback()} />
+                })
+            })
+        };
+        modal.open(html! {
+          <ScenarioEditor {index} onclose={back_to_settings} />
+        });
+    });
+
+    let import_file = {
+        let local = local.clone();
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            let file = match input.files().and_then(|files| files.get(0)) {
+                Some(file) => gloo::file::File::from(file),
+                None => return,
+            };
+            // Allow picking the same file again after changing it
+            input.set_value("");
+            let local = local.clone();
+            let status = status.clone();
+            let tr = tr.clone();
+            spawn_local(async move {
+                let result = gloo::file::futures::read_as_text(&file)
+                    .await
+                    .map_err(|error| anyhow!("{error}"))
+                    .and_then(|json| import(&file.name(), &json));
+                match result {
+                    Ok((meta, scenario, warnings)) => {
+                        let message = if warnings.is_empty() {
+                            tr.format("imported", Some(&fluent_args!["name" => meta.name.clone()]))
+                        } else {
+                            tr.format(
+                                "imported-with-warnings",
+                                Some(&fluent_args![
+                                    "name" => meta.name.clone(),
+                                    "warnings" => warnings
+                                        .iter()
+                                        .map(|issue| issue_text(&tr, issue))
+                                        .join("; "),
+                                ]),
+                            )
+                        };
+                        status.set(Some(Ok(message)));
+                        // Importing a file again replaces the old version
+                        let mut scenarios = local.0.clone();
+                        scenarios.retain(|(m, _)| m.filename != meta.filename);
+                        scenarios.push((meta, scenario));
+                        local.set(LocalScenarios(scenarios));
+                    }
+                    Err(error) => {
+                        let error = match error.downcast_ref::<InvalidScenario>() {
+                            Some(InvalidScenario(errors)) => {
+                                errors.iter().map(|issue| issue_text(&tr, issue)).join("; ")
+                            }
+                            None => error_text(&tr, &error),
+                        };
+                        status.set(Some(Err(
+                            tr.format("import-failed", Some(&fluent_args!["error" => error]))
+                        )))
+                    }
+                }
+            });
+        })
+    };
+
+    let entries = local.0.iter().enumerate().map(|(ix, (meta, _))| {
+        let remove = {
+            let local = local.clone();
+            Callback::from(move |_| {
+                let mut scenarios = local.0.clone();
+                scenarios.remove(ix);
+                local.set(LocalScenarios(scenarios));
+            })
+        };
+        let edit = open_editor.reform(move |_| Some(ix));
+        html! {
+          <div class="setting">
+            <span class="name">{ meta.name.clone() }</span>
+            <span>
+              <button onclick={edit}>{ tr.get("edit") }</button>
+              <button onclick={remove}>{ tr.get("remove") }</button>
+            </span>
+          </div>
+        }
+    });
+
+    html! {
+      <>
+        <h2>{ tr.get("local-scenarios") }</h2>
+        { for entries }
+        <div class="setting">
+          <StatusLine status={(*status).clone()} />
+          <span>
+            <button onclick={open_editor.reform(|_| None)}>{ tr.get("new-scenario") }</button>
+            <label class="button">
+              { tr.get("import-scenario") }
+              <input type="file" accept=".json,application/json" onchange={import_file} />
+            </label>
+          </span>
+        </div>
+      </>
+    }
+}
@@ -1,45 +1,87 @@
 use bounce::BounceRoot;
-use unic_langid::LanguageIdentifier;
+use gloo::events::EventListener;
 use yew::prelude::*;
 
-use crate::{app::App, modal::ModalHost, pwa::PwaHandler};
+use crate::{
+    app::App,
+    language::LanguageProvider,
+    link::{current_fragment, LinkHandler},
+    modal::ModalHost,
+    pwa::PwaHandler,
+};
 
 mod app;
+mod editor;
+mod history;
+mod i18n;
+mod language;
+mod link;
+mod loader;
+mod local_scenarios;
 mod modal;
-mod pairandomizer_core;
 mod pwa;
+mod qr;
+mod roster;
 mod settings;
+mod share;
+mod statistics;
 
 fn main() {
     yew::Renderer::<AppShell>::new().render();
 }
 
 struct AppShell {
-    language: LanguageIdentifier,
+    /// The URL fragment to handle, for links to results.
+    fragment: Option<AttrValue>,
+    _hashchange: EventListener,
+}
+
+enum Msg {
+    /// The URL fragment changed, e.g. because a link was opened while the app
+    /// is already running.
+    FragmentChanged,
+    /// The fragment was handled and can be forgotten.
+    FragmentHandled,
 }
 
 impl Component for AppShell {
-    type Message = ();
+    type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        let navigator = gloo::utils::window().navigator();
-        let language = navigator
-            .language()
-            .and_then(|lang| lang.parse().ok())
-            .unwrap_or_else(|| unic_langid::langid!("en-US"));
-        Self { language }
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let hashchange = EventListener::new(&gloo::utils::window(), "hashchange", move |_| {
+            link.send_message(Msg::FragmentChanged)
+        });
+        Self {
+            fragment: current_fragment().map(AttrValue::from),
+            _hashchange: hashchange,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Msg) -> bool {
+        let fragment = match msg {
+            Msg::FragmentChanged => current_fragment().map(AttrValue::from),
+            Msg::FragmentHandled => None,
+        };
+        let changed = self.fragment != fragment;
+        self.fragment = fragment;
+        changed
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
           <BounceRoot>
-            <ContextProvider<LanguageIdentifier> context={self.language.clone()}>
+            <LanguageProvider>
               <ModalHost>
                 <App />
                 <PwaHandler />
+                <LinkHandler
+                  fragment={self.fragment.clone()}
+                  onhandled={ctx.link().callback(|_| Msg::FragmentHandled)}
+                  />
               </ModalHost>
-            </ContextProvider<LanguageIdentifier>>
+            </LanguageProvider>
           </BounceRoot>
         }
     }
@@ -39,6 +39,24 @@ pub fn dialog(props: &DialogProps) -> Html {
     }
 }
 
+/// A message about how an action went, and whether it failed.
+pub type Status = Option<Result<String, String>>;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct StatusLineProps {
+    pub status: Status,
+}
+
+/// Shows a [`Status`] in a dialog, marking failures as errors.
+#[function_component(StatusLine)]
+pub fn status_line(props: &StatusLineProps) -> Html {
+    match &props.status {
+        Some(Ok(message)) => html! { <span class="status">{ message }</span> },
+        Some(Err(message)) => html! { <span class="status error">{ message }</span> },
+        None => Html::default(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ModalAction {
     Open(Html),
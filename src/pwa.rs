@@ -3,7 +3,10 @@ use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{ServiceWorker, ServiceWorkerState};
 use yew::prelude::*;
 
-use crate::modal::{Dialog, Modal};
+use crate::{
+    i18n::use_translations,
+    modal::{Dialog, Modal},
+};
 
 pub struct PwaHandler {
     update_available: bool,
@@ -110,14 +113,28 @@ impl Component for PwaHandler {
         let update = ctx.link().callback(|_| Msg::ActivateUpdate);
         html! {
           <Modal onclose={&close}>
-            <Dialog title="Update available!">
-              <div>{"Update now?"}</div>
-              <div class="buttons">
-                <button onclick={close}>{"Ignore"}</button>
-                <button onclick={update}>{"Yes"}</button>
-              </div>
-            </Dialog>
+            <UpdateDialog onignore={close} onupdate={update} />
           </Modal>
         }
     }
 }
+
+#[derive(Clone, PartialEq, Properties)]
+struct UpdateDialogProps {
+    onignore: Callback<MouseEvent>,
+    onupdate: Callback<MouseEvent>,
+}
+
+#[function_component(UpdateDialog)]
+fn update_dialog(props: &UpdateDialogProps) -> Html {
+    let tr = use_translations();
+    html! {
+      <Dialog title={tr.get("update-available")}>
+        <div>{ tr.get("update-now") }</div>
+        <div class="buttons">
+          <button onclick={&props.onignore}>{ tr.get("ignore") }</button>
+          <button onclick={&props.onupdate}>{ tr.get("yes") }</button>
+        </div>
+      </Dialog>
+    }
+}
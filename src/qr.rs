@@ -0,0 +1,58 @@
+//! QR codes for links, to pass them on to people in the same room.
+
+use fluent::fluent_args;
+use qrcode::{render::svg, QrCode};
+use yew::prelude::*;
+
+use crate::{i18n::use_translations, modal::Dialog};
+
+/// Renders `text` as a QR code in an SVG image.
+///
+/// Fails if there is too much text to fit into a QR code.
+fn qr_svg(text: &str) -> Result<String, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|error| error.to_string())?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(256, 256)
+        .quiet_zone(true)
+        .build())
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct QrCodeDialogProps {
+    pub title: String,
+    pub url: AttrValue,
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Shows a QR code for `url`, along with the URL itself.
+#[function_component(QrCodeDialog)]
+pub fn qr_code_dialog(props: &QrCodeDialogProps) -> Html {
+    let tr = use_translations();
+    let svg = use_memo(props.url.clone(), |url| qr_svg(url));
+
+    let code = match &*svg {
+        // The SVG is generated by us and contains nothing from the URL but
+        // its encoded modules
+        Ok(svg) => html! {
+          <div class="code">{ Html::from_html_unchecked(AttrValue::from(svg.clone())) }</div>
+        },
+        Err(error) => html! {
+          <span class="status error">
+            { tr.format("qr-code-failed", Some(&fluent_args!["error" => error.clone()])) }
+          </span>
+        },
+    };
+    html! {
+      <Dialog title={props.title.clone()}>
+        <div class="qr-code">
+          { code }
+          <small>{ &props.url }</small>
+        </div>
+        <div class="buttons">
+          <span />
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
+        </div>
+      </Dialog>
+    }
+}
@@ -0,0 +1,205 @@
+//! Passing the list of names on to others through a link.
+
+use bounce::prelude::*;
+use fluent::fluent_args;
+use pairandomizer_core::RosterLink;
+use yew::prelude::*;
+
+use crate::{
+    app::{error_text, scenario_title, AvailableIndex, Names},
+    i18n::use_translations,
+    link::link_url,
+    modal::{Dialog, ModalSender, Status, StatusLine},
+    qr::QrCodeDialog,
+    settings::Settings,
+    share::{copy_with_status, share_with_status},
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ShareRosterDialogProps {
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Shows a link to the current names, optionally along with the selected
+/// scenario and scene.
+#[function_component(ShareRosterDialog)]
+pub fn share_roster_dialog(props: &ShareRosterDialogProps) -> Html {
+    let modal = use_context::<ModalSender>().unwrap();
+    let tr = use_translations();
+    let names = use_atom_value::<Names>();
+    let settings = use_atom_value::<Settings>();
+    let index = use_selector_value::<AvailableIndex>();
+    let include_selection = use_state(|| false);
+    let status = use_state(|| None as Status);
+
+    let has_selection = settings.scenario_index.is_some();
+    let link = if *include_selection {
+        RosterLink::new(
+            &names.0,
+            Option::as_ref(&index),
+            settings.scenario_index,
+            settings.scene_index,
+        )
+    } else {
+        RosterLink::new(&names.0, None, None, None)
+    };
+    let url = link_url(&link.to_fragment());
+
+    let toggle_selection = {
+        let include_selection = include_selection.clone();
+        Callback::from(move |e: Event| {
+            let cb = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            include_selection.set(cb.checked());
+        })
+    };
+    let copy = {
+        let url = url.clone();
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |_| copy_with_status(&tr, &status, url.clone(), "link-copied"))
+    };
+    let share = {
+        let url = url.clone();
+        let status = status.setter();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            share_with_status(
+                &tr,
+                &status,
+                tr.get("share-roster"),
+                url.clone(),
+                "link-copied",
+            )
+        })
+    };
+    let show_qr_code = {
+        let url = url.clone();
+        let tr = tr.clone();
+        Callback::from(move |_| {
+            let back_to_roster = {
+                let modal = modal.clone();
+                Callback::from(move |_| {
+                    modal.open(html! {
+                      <ShareRosterDialog onclose={modal.close_callback()} />
+                    })
+                })
+            };
+            modal.open(html! {
+              <QrCodeDialog
+                title={tr.get("share-roster")}
+                url={url.clone()}
+                onclose={back_to_roster}
+                />
+            });
+        })
+    };
+
+    html! {
+      <Dialog title={tr.get("share-roster")}>
+        <div class="roster">
+          <span class="status">
+            { tr.format("roster-names", Some(&fluent_args!["count" => names.0.len()])) }
+          </span>
+          if has_selection {
+            <label>
+              <input
+                type="checkbox"
+                checked={*include_selection}
+                onchange={toggle_selection}
+                />
+              { tr.get("include-selection") }
+            </label>
+          }
+          <input type="text" readonly=true value={url} />
+        </div>
+        <div class="copy">
+          <button onclick={share}>{ tr.get("share") }</button>
+          <button onclick={copy}>{ tr.get("copy-link") }</button>
+          <button onclick={show_qr_code}>{ tr.get("show-qr-code") }</button>
+        </div>
+        <StatusLine status={(*status).clone()} />
+        <div class="buttons">
+          <span />
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
+        </div>
+      </Dialog>
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ImportRosterDialogProps {
+    pub link: RosterLink,
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Asks whether to replace the names, and the selected scenario if the link
+/// has one, with those of a roster link.
+#[function_component(ImportRosterDialog)]
+pub fn import_roster_dialog(props: &ImportRosterDialogProps) -> Html {
+    let tr = use_translations();
+    let names = use_atom::<Names>();
+    let settings = use_atom::<Settings>();
+    let index = use_selector_value::<AvailableIndex>();
+
+    // Without the scenario, the names can still be imported
+    let selection = match Option::as_ref(&index) {
+        Some(index) => props.link.selection(index).map(Some),
+        None => Ok(None),
+    };
+    let selection_text = match (&selection, Option::as_ref(&index)) {
+        (Ok(Some((Some(scenario_index), scene_index))), Some(index)) => {
+            let (meta, scenario) = &index.scenarios[*scenario_index];
+            let mut text = scenario_title(&tr, meta);
+            if let Some(scene) = scene_index.and_then(|ix| scenario.scenes.get(ix)) {
+                text += " - ";
+                text += &scene.name;
+            }
+            Some(Ok(tr.format(
+                "roster-selection",
+                Some(&fluent_args!["selection" => text]),
+            )))
+        }
+        (Err(error), _) => Some(Err(tr.format(
+            "roster-selection-unavailable",
+            Some(&fluent_args!["error" => error_text(&tr, error)]),
+        ))),
+        _ => None,
+    };
+
+    // Wait for the scenarios, so the selection isn't silently dropped
+    let waiting = props.link.scenario.is_some() && index.is_none();
+    let import = {
+        let link_names = props.link.names.clone();
+        let selection = selection.ok().flatten();
+        let onclose = props.onclose.clone();
+        Callback::from(move |e| {
+            names.set(Names(link_names.clone()));
+            if let Some((scenario_index, scene_index)) = selection {
+                let mut new_settings = Settings::clone(&settings);
+                new_settings.scenario_index = scenario_index;
+                new_settings.scene_index = scene_index;
+                settings.set(new_settings);
+            }
+            onclose.emit(e);
+        })
+    };
+    let link_names = props
+        .link
+        .names
+        .iter()
+        .map(|name| html! { <li>{ name }</li> });
+
+    html! {
+      <Dialog title={tr.get("import-roster")}>
+        <div class="roster">
+          <span>{ tr.get("import-roster-question") }</span>
+          <ul>{ for link_names }</ul>
+          <StatusLine status={selection_text} />
+        </div>
+        <div class="buttons">
+          <button onclick={&props.onclose}>{ tr.get("cancel") }</button>
+          <button onclick={import} disabled={waiting}>{ tr.get("import") }</button>
+        </div>
+      </Dialog>
+    }
+}
@@ -1,12 +1,21 @@
 use std::rc::Rc;
 
 use bounce::prelude::*;
+use fluent::fluent_args;
 use gloo::storage::{LocalStorage, Storage};
+use itertools::Itertools;
+use pairandomizer_core::PickOptions;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::UnwrapThrowExt;
 use yew::prelude::*;
 
-use crate::{app::CurrentIndex, modal::Dialog};
+use crate::{
+    app::{scenario_title, AvailableIndex},
+    i18n::use_translations,
+    language::browser_languages,
+    local_scenarios::LocalScenariosSettings,
+    modal::Dialog,
+};
 
 const STORAGE_KEY: &str = "settings";
 
@@ -16,6 +25,24 @@ pub struct Settings {
     pub ignore_language: bool,
     pub scenario_index: Option<usize>,
     pub scene_index: Option<usize>,
+    /// Whether beta scenarios may be picked at random.
+    #[serde(default)]
+    pub enable_beta: bool,
+    /// The language to use instead of the browser's.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl Settings {
+    /// The restrictions these settings put on picking a scenario.
+    pub fn pick_options(&self) -> PickOptions {
+        PickOptions {
+            scenario_index: self.scenario_index,
+            scene_index: self.scene_index,
+            ignore_language: self.ignore_language,
+            enable_beta: self.enable_beta,
+        }
+    }
 }
 
 impl Default for Settings {
@@ -26,6 +53,8 @@ impl Default for Settings {
                 ignore_language: false,
                 scenario_index: None,
                 scene_index: None,
+                enable_beta: false,
+                language: None,
             }
         })
     }
@@ -43,8 +72,9 @@ pub struct SettingsDialogProps {
 
 #[function_component(SettingsDialog)]
 pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
+    let tr = use_translations();
     let settings = use_atom::<Settings>();
-    let index = use_atom_value::<CurrentIndex>();
+    let index = use_selector_value::<AvailableIndex>();
     let index = Option::as_ref(&index);
 
     let scenario_options = index
@@ -52,10 +82,29 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
             index
                 .scenarios
                 .iter()
-                .map(|s| s.0.name.clone())
+                .map(|(meta, _)| scenario_title(&tr, meta))
                 .collect::<Vec<_>>()
         })
         .unwrap_or_default();
+    // The browser's languages and those of the scenarios
+    let browser = browser_languages();
+    let language_options = browser
+        .iter()
+        .map(ToString::to_string)
+        .chain(
+            index
+                .into_iter()
+                .flat_map(|index| &index.scenarios)
+                .flat_map(|(meta, _)| meta.lang.iter().chain(&meta.languages).cloned()),
+        )
+        .chain(settings.language.clone())
+        .sorted()
+        .dedup()
+        .collect::<Vec<_>>();
+    let automatic_language = tr.format(
+        "language-automatic",
+        Some(&fluent_args!["languages" => browser.iter().map(ToString::to_string).join(", ")]),
+    );
     let scene_options = settings
         .scenario_index
         .and_then(|ix| index.map(|index| (index, ix)))
@@ -78,6 +127,24 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
             settings.set(new_settings);
         })
     };
+    let update_enable_beta = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let cb = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            let mut new_settings = Settings::clone(&settings);
+            new_settings.enable_beta = cb.checked();
+            settings.set(new_settings);
+        })
+    };
+    let update_language = {
+        let settings = settings.clone();
+        let language_options = language_options.clone();
+        Callback::from(move |index: Option<usize>| {
+            let mut new_settings = Settings::clone(&settings);
+            new_settings.language = index.and_then(|ix| language_options.get(ix).cloned());
+            settings.set(new_settings);
+        })
+    };
     let update_scenario = {
         let settings = settings.clone();
         Callback::from(move |index| {
@@ -101,10 +168,23 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
     };
 
     html! {
-      <Dialog title="Settings">
+      <Dialog title={tr.get("settings")}>
         <div class="settings">
           <div class="setting">
-            <span class="name">{ "Allow all languages" }</span>
+            <span class="name">{ tr.get("setting-language") }</span>
+            <Dropdown
+              value={settings
+                  .language
+                  .as_ref()
+                  .and_then(|lang| language_options.iter().position(|option| option == lang))}
+              options={language_options}
+              none_label={automatic_language}
+              onselect={update_language}
+              disabled={false}
+              />
+          </div>
+          <div class="setting">
+            <span class="name">{ tr.get("setting-ignore-language") }</span>
             <input
               type="checkbox"
               checked={settings.ignore_language}
@@ -112,7 +192,15 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
               />
           </div>
           <div class="setting">
-            <span class="name">{ "Fixed scenario:" }</span>
+            <span class="name">{ tr.get("setting-enable-beta") }</span>
+            <input
+              type="checkbox"
+              checked={settings.enable_beta}
+              onchange={update_enable_beta}
+              />
+          </div>
+          <div class="setting">
+            <span class="name">{ tr.get("setting-fixed-scenario") }</span>
             <Dropdown
               options={scenario_options}
               value={settings.scenario_index}
@@ -121,7 +209,7 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
               />
           </div>
           <div class="setting">
-            <span class="name">{ "Fixed scene:" }</span>
+            <span class="name">{ tr.get("setting-fixed-scene") }</span>
             <Dropdown
               options={scene_options}
               value={settings.scene_index}
@@ -129,10 +217,11 @@ pub fn settings_dialog(props: &SettingsDialogProps) -> Html {
               disabled={index.is_none() || settings.scenario_index.is_none()}
               />
           </div>
+          <LocalScenariosSettings />
         </div>
         <div class="buttons">
           <span />
-          <button onclick={&props.onclose}>{"Close"}</button>
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
         </div>
       </Dialog>
     }
@@ -144,10 +233,14 @@ struct DropdownProps {
     value: Option<usize>,
     onselect: Callback<Option<usize>>,
     disabled: bool,
+    /// The label of the option for no value, "None" if not set.
+    #[prop_or_default]
+    none_label: Option<AttrValue>,
 }
 
 #[function_component(Dropdown)]
 fn dropdown(props: &DropdownProps) -> Html {
+    let tr = use_translations();
     let options = props.options.iter().enumerate().map(|(ix, option)| {
         let selected = props.value == Some(ix);
         html! {
@@ -169,7 +262,7 @@ fn dropdown(props: &DropdownProps) -> Html {
     html! {
       <select {onchange} disabled={props.disabled}>
         <option value="none" selected={props.value.is_none()}>
-          { "None" }
+          { props.none_label.clone().unwrap_or_else(|| tr.get("none").into()) }
         </option>
         { for options }
       </select>
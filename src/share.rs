@@ -0,0 +1,94 @@
+//! Getting results out of the app and into other ones.
+
+use fluent::fluent_args;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::UseStateSetter;
+
+use crate::{i18n::Translations, modal::Status};
+
+/// Puts `text` on the clipboard.
+///
+/// Fails if the browser doesn't allow it, e.g. outside of a secure context.
+pub async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let navigator = gloo::utils::window().navigator();
+    if !JsValue::from_str("clipboard").js_in(&navigator) {
+        return Err("the clipboard is not available".to_string());
+    }
+    JsFuture::from(navigator.clipboard().write_text(text))
+        .await
+        .map(|_| ())
+        .map_err(|error| format!("{error:?}"))
+}
+
+/// What became of something passed to [`share`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareOutcome {
+    /// Handed to another app through the system's share sheet.
+    Shared,
+    /// The user closed the share sheet without picking an app.
+    Cancelled,
+    /// Sharing isn't supported, so it was put on the clipboard instead.
+    Copied,
+}
+
+/// Shares `text` through the system's share sheet, putting it on the
+/// clipboard where that isn't supported.
+pub async fn share(title: &str, text: &str) -> Result<ShareOutcome, String> {
+    let navigator = gloo::utils::window().navigator();
+    if !JsValue::from_str("share").js_in(&navigator) {
+        return copy_to_clipboard(text).await.map(|()| ShareOutcome::Copied);
+    }
+    let data = web_sys::ShareData::new();
+    data.set_title(title);
+    data.set_text(text);
+    match JsFuture::from(navigator.share_with_data(&data)).await {
+        Ok(_) => Ok(ShareOutcome::Shared),
+        Err(error) => match error.dyn_into::<web_sys::DomException>() {
+            Ok(error) if error.name() == "AbortError" => Ok(ShareOutcome::Cancelled),
+            Ok(error) => Err(error.message()),
+            Err(error) => Err(format!("{error:?}")),
+        },
+    }
+}
+
+/// Puts `text` on the clipboard in the background, then shows the message
+/// `copied` or why it failed in `status`.
+pub fn copy_with_status(
+    tr: &Translations,
+    status: &UseStateSetter<Status>,
+    text: String,
+    copied: &'static str,
+) {
+    let tr = tr.clone();
+    let status = status.clone();
+    spawn_local(async move {
+        status.set(Some(match copy_to_clipboard(&text).await {
+            Ok(()) => Ok(tr.get(copied)),
+            Err(error) => Err(tr.format("copy-failed", Some(&fluent_args!["error" => error]))),
+        }));
+    });
+}
+
+/// Shares `text` in the background like [`share`], then shows the message
+/// `copied` if it was put on the clipboard instead, or why it failed, in
+/// `status`.
+pub fn share_with_status(
+    tr: &Translations,
+    status: &UseStateSetter<Status>,
+    title: String,
+    text: String,
+    copied: &'static str,
+) {
+    let tr = tr.clone();
+    let status = status.clone();
+    spawn_local(async move {
+        match share(&title, &text).await {
+            Ok(ShareOutcome::Shared | ShareOutcome::Cancelled) => status.set(None),
+            Ok(ShareOutcome::Copied) => status.set(Some(Ok(tr.get(copied)))),
+            Err(error) => status.set(Some(Err(
+                tr.format("share-failed", Some(&fluent_args!["error" => error]))
+            ))),
+        }
+    });
+}
@@ -0,0 +1,91 @@
+use bounce::prelude::*;
+use fluent::fluent_args;
+use pairandomizer_core::Statistics;
+use yew::prelude::*;
+
+use crate::{history::History, i18n::use_translations, modal::Dialog};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct StatisticsDialogProps {
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Shows how often everyone was grouped with whom and what was used, over
+/// all results in the history.
+#[function_component(StatisticsDialog)]
+pub fn statistics_dialog(props: &StatisticsDialogProps) -> Html {
+    let tr = use_translations();
+    let history = use_atom_value::<History>();
+    let stats = use_memo(history, |history| {
+        Statistics::new(history.0.iter().map(|entry| &entry.result))
+    });
+
+    let body = if stats.results == 0 {
+        html! { <span class="status">{ tr.get("history-empty") }</span> }
+    } else {
+        let header = stats.names.iter().map(|name| html! { <th>{ name }</th> });
+        let rows = stats.names.iter().map(|a| {
+            let cells = stats.names.iter().map(|b| {
+                if a == b {
+                    html! { <td class="self">{ "–" }</td> }
+                } else {
+                    let count = stats.pair_count(a, b);
+                    html! { <td class={classes!((count == 0).then_some("never"))}>{ count }</td> }
+                }
+            });
+            html! { <tr><th>{ a }</th>{ for cells }</tr> }
+        });
+        let never_paired = stats.never_paired();
+        let never_paired = if never_paired.is_empty() {
+            html! { <span class="status">{ tr.get("everyone-paired") }</span> }
+        } else {
+            let pairs = never_paired
+                .iter()
+                .map(|(a, b)| html! { <li>{ format!("{a} & {b}") }</li> });
+            html! { <ul>{ for pairs }</ul> }
+        };
+        let scenarios = stats
+            .scenarios
+            .iter()
+            .map(|(name, count)| html! { <li>{ format!("{name}: {count}") }</li> });
+        let scenes = stats.scenes.iter().map(|((scenario, scene), count)| {
+            html! { <li>{ format!("{scenario} - {scene}: {count}") }</li> }
+        });
+        let solo = stats.names.iter().map(|name| {
+            let count = stats.solo.get(name).copied().unwrap_or(0);
+            html! { <li>{ format!("{name}: {count}") }</li> }
+        });
+        html! {
+          <>
+            <span class="status">
+              { tr.format("statistics-results", Some(&fluent_args!["count" => stats.results])) }
+            </span>
+            <h2>{ tr.get("pair-frequency") }</h2>
+            <div class="matrix">
+              <table>
+                <tr><th />{ for header }</tr>
+                { for rows }
+              </table>
+            </div>
+            <h2>{ tr.get("never-paired") }</h2>
+            { never_paired }
+            <h2>{ tr.get("most-used-scenarios") }</h2>
+            <ul>{ for scenarios }</ul>
+            <h2>{ tr.get("most-used-scenes") }</h2>
+            <ul>{ for scenes }</ul>
+            <h2>{ tr.get("solo-counts") }</h2>
+            <ul>{ for solo }</ul>
+          </>
+        }
+    };
+
+    html! {
+      <Dialog title={tr.get("statistics")}>
+        <div class="statistics">{ body }</div>
+        <div class="buttons">
+          <span />
+          <button onclick={&props.onclose}>{ tr.get("close") }</button>
+        </div>
+      </Dialog>
+    }
+}